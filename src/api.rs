@@ -15,7 +15,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::{env, fmt};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use backoff::backoff::Backoff;
 use brotli2::write::BrotliEncoder;
 use chrono::{DateTime, Duration, FixedOffset, Utc};
@@ -28,6 +28,7 @@ use parking_lot::{Mutex, RwLock};
 use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 use regex::{Captures, Regex};
 use sentry::protocol::{Exception, Values};
+use sentry::types::Dsn;
 use serde::de::{DeserializeOwned, Deserializer};
 use serde::{Deserialize, Serialize};
 use sha1_smol::Digest;
@@ -37,7 +38,9 @@ use url::Url;
 use uuid::Uuid;
 
 use crate::config::{Auth, Config};
-use crate::constants::{ARCH, EXT, PLATFORM, RELEASE_REGISTRY_LATEST_URL, VERSION};
+use crate::constants::{
+    ARCH, DEFAULT_RETRY_AFTER, EXT, PLATFORM, RELEASE_REGISTRY_LATEST_URL, USER_AGENT, VERSION,
+};
 use crate::utils::android::AndroidManifest;
 use crate::utils::file_upload::UploadContext;
 use crate::utils::http::{self, is_absolute_url, parse_link_header};
@@ -49,6 +52,7 @@ use crate::utils::xcode::InfoPlist;
 
 const QUERY_ENCODE_SET: AsciiSet = CONTROLS.add(b' ').add(b'"').add(b'#').add(b'<').add(b'>');
 const DEFAULT_ENCODE_SET: AsciiSet = QUERY_ENCODE_SET.add(b'`').add(b'?').add(b'{').add(b'}');
+const QUERY_VALUE_ENCODE_SET: AsciiSet = QUERY_ENCODE_SET.add(b'&').add(b'+').add(b'=');
 
 /// Wrapper that escapes arguments for URL path segments.
 pub struct PathArg<A: fmt::Display>(A);
@@ -120,7 +124,7 @@ impl FromStr for Pagination {
 
 impl<A: fmt::Display> fmt::Display for QueryArg<A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        utf8_percent_encode(&format!("{}", self.0), &QUERY_ENCODE_SET).fmt(f)
+        utf8_percent_encode(&format!("{}", self.0), &QUERY_VALUE_ENCODE_SET).fmt(f)
     }
 }
 
@@ -870,6 +874,35 @@ impl Api {
         }
     }
 
+    /// Returns all releases of a project, following pagination.
+    pub fn list_all_releases(&self, org: &str, project: &str) -> ApiResult<Vec<ReleaseInfo>> {
+        let mut rv = vec![];
+        let mut cursor = "".to_string();
+        loop {
+            let resp = self.get(&format!(
+                "/projects/{}/{}/releases/?cursor={}",
+                PathArg(org),
+                PathArg(project),
+                QueryArg(&cursor)
+            ))?;
+            if resp.status() == 404 || (resp.status() == 400 && !cursor.is_empty()) {
+                if rv.is_empty() {
+                    return Err(ApiErrorKind::ProjectNotFound.into());
+                } else {
+                    break;
+                }
+            }
+            let pagination = resp.pagination();
+            rv.extend(resp.convert::<Vec<ReleaseInfo>>()?);
+            if let Some(next) = pagination.into_next_cursor() {
+                cursor = next;
+            } else {
+                break;
+            }
+        }
+        Ok(rv)
+    }
+
     /// Looks up a release commits and returns it.  If it does not exist `None`
     /// will be returned.
     pub fn get_release_commits(
@@ -970,6 +1003,54 @@ impl Api {
         .map(|_| true)
     }
 
+    /// Lists issues within a project that match a provided filter.  This
+    /// selects the same issues that `bulk_update_issue` would update for
+    /// that filter.  At most `max_rows` issues are returned if a limit is
+    /// given.
+    pub fn list_issues(
+        &self,
+        org: &str,
+        project: &str,
+        filter: &IssueFilter,
+        max_rows: Option<usize>,
+    ) -> ApiResult<Vec<Issue>> {
+        let qs = match filter.get_search_query_string() {
+            None => {
+                return Ok(vec![]);
+            }
+            Some(qs) => qs,
+        };
+        let mut rv = vec![];
+        let mut cursor = "".to_string();
+        loop {
+            let mut path = format!(
+                "/projects/{}/{}/issues/?cursor={}",
+                PathArg(org),
+                PathArg(project),
+                QueryArg(&cursor)
+            );
+            if !qs.is_empty() {
+                path.push('&');
+                path.push_str(&qs);
+            }
+            let resp = self.get(&path)?;
+            let pagination = resp.pagination();
+            rv.extend(resp.convert_rnf::<Vec<Issue>>(ApiErrorKind::ProjectNotFound)?);
+            if let Some(max_rows) = max_rows {
+                if rv.len() >= max_rows {
+                    rv.truncate(max_rows);
+                    break;
+                }
+            }
+            if let Some(next) = pagination.into_next_cursor() {
+                cursor = next;
+            } else {
+                break;
+            }
+        }
+        Ok(rv)
+    }
+
     /// Finds the latest release for sentry-cli on GitHub.
     pub fn get_latest_sentrycli_release(&self) -> ApiResult<Option<SentryCliRelease>> {
         let resp = self.get(RELEASE_REGISTRY_LATEST_URL)?;
@@ -1030,6 +1111,83 @@ impl Api {
         Ok(state.missing)
     }
 
+    /// Lists the debug information files of a project.
+    ///
+    /// The query matches debug IDs, code IDs and object names. The file
+    /// formats restrict the listing to the given symbol types.
+    pub fn list_difs(
+        &self,
+        org: &str,
+        project: &str,
+        query: Option<&str>,
+        file_formats: &[&str],
+    ) -> ApiResult<Vec<DebugInfoFile>> {
+        let mut rv = vec![];
+        let mut cursor = "".to_string();
+        loop {
+            let mut path = format!(
+                "/projects/{}/{}/files/dsyms/?cursor={}",
+                PathArg(org),
+                PathArg(project),
+                QueryArg(&cursor),
+            );
+            if let Some(query) = query {
+                path.push_str(&format!("&query={}", QueryArg(query)));
+            }
+            for file_format in file_formats {
+                path.push_str(&format!("&file_formats={}", QueryArg(file_format)));
+            }
+            let resp = self.get(&path)?;
+            let pagination = resp.pagination();
+            rv.extend(resp.convert_rnf::<Vec<DebugInfoFile>>(ApiErrorKind::ProjectNotFound)?);
+            if let Some(next) = pagination.into_next_cursor() {
+                cursor = next;
+            } else {
+                break;
+            }
+        }
+        Ok(rv)
+    }
+
+    /// Downloads a single debug information file into the given file.
+    pub fn download_dif(
+        &self,
+        org: &str,
+        project: &str,
+        file_id: &str,
+        dst: &mut File,
+    ) -> ApiResult<()> {
+        let path = format!(
+            "/projects/{}/{}/files/dsyms/?id={}",
+            PathArg(org),
+            PathArg(project),
+            QueryArg(file_id)
+        );
+        let resp = self.download_with_progress(&path, dst)?;
+        if resp.status() == 404 {
+            resp.convert_rnf(ApiErrorKind::ResourceNotFound)
+        } else {
+            resp.into_result().map(|_| ())
+        }
+    }
+
+    /// Deletes a single debug information file.  Returns `true` if the file
+    /// was deleted or `false` otherwise.
+    pub fn delete_dif(&self, org: &str, project: &str, file_id: &str) -> ApiResult<bool> {
+        let path = format!(
+            "/projects/{}/{}/files/dsyms/?id={}",
+            PathArg(org),
+            PathArg(project),
+            QueryArg(file_id)
+        );
+        let resp = self.delete(&path)?;
+        if resp.status() == 404 {
+            Ok(false)
+        } else {
+            resp.into_result().map(|_| true)
+        }
+    }
+
     /// Uploads a ZIP archive containing DIFs from the given path.
     pub fn upload_dif_archive(
         &self,
@@ -1353,6 +1511,65 @@ impl Api {
         Ok(rv)
     }
 
+    /// Looks up a monitor by its ID or slug and returns it.  If it does not
+    /// exist `None` will be returned.
+    pub fn get_organization_monitor(&self, org: &str, monitor: &str) -> ApiResult<Option<Monitor>> {
+        let path = format!(
+            "/organizations/{}/monitors/{}/",
+            PathArg(org),
+            PathArg(monitor)
+        );
+        let resp = self.get(&path)?;
+        if resp.status() == 404 {
+            Ok(None)
+        } else {
+            resp.convert()
+        }
+    }
+
+    /// Creates a new monitor within an organization.
+    pub fn create_organization_monitor(
+        &self,
+        org: &str,
+        monitor: &CreateMonitor,
+    ) -> ApiResult<Monitor> {
+        let path = format!("/organizations/{}/monitors/", PathArg(org));
+        self.post(&path, monitor)?
+            .convert_rnf(ApiErrorKind::OrganizationNotFound)
+    }
+
+    /// Updates the monitor with the given ID or slug.
+    pub fn update_organization_monitor(
+        &self,
+        org: &str,
+        monitor: &str,
+        changes: &UpdateMonitor,
+    ) -> ApiResult<Monitor> {
+        let path = format!(
+            "/organizations/{}/monitors/{}/",
+            PathArg(org),
+            PathArg(monitor)
+        );
+        self.put(&path, changes)?
+            .convert_rnf(ApiErrorKind::ResourceNotFound)
+    }
+
+    /// Deletes the monitor with the given ID or slug.  Returns `true` if it
+    /// was deleted or `false` if it did not exist.
+    pub fn delete_organization_monitor(&self, org: &str, monitor: &str) -> ApiResult<bool> {
+        let path = format!(
+            "/organizations/{}/monitors/{}/",
+            PathArg(org),
+            PathArg(monitor)
+        );
+        let resp = self.delete(&path)?;
+        if resp.status() == 404 {
+            Ok(false)
+        } else {
+            resp.into_result().map(|_| true)
+        }
+    }
+
     /// Create a new checkin for a monitor
     pub fn create_monitor_checkin(
         &self,
@@ -1386,6 +1603,32 @@ impl Api {
         resp.convert()
     }
 
+    /// Sends a serialized envelope straight to the ingestion endpoint of the
+    /// given DSN.
+    pub fn send_envelope(&self, dsn: &Dsn, envelope: &[u8]) -> ApiResult<EnvelopeResponse> {
+        let resp = self
+            .request(Method::Post, dsn.envelope_api_url().as_str())?
+            .with_header("X-Sentry-Auth", &dsn.to_auth(Some(USER_AGENT)).to_string())?
+            .with_header("Content-Type", "application/x-sentry-envelope")?
+            .with_body(envelope.to_vec())?
+            .send()?;
+
+        if resp.ok() {
+            return Ok(EnvelopeResponse::Accepted);
+        }
+
+        if resp.status() == 429 {
+            let retry_after = resp
+                .get_header("x-sentry-rate-limits")
+                .and_then(parse_rate_limits)
+                .or_else(|| resp.get_header("retry-after").and_then(|x| x.parse().ok()))
+                .unwrap_or(DEFAULT_RETRY_AFTER);
+            return Ok(EnvelopeResponse::RateLimited(retry_after));
+        }
+
+        Ok(EnvelopeResponse::Failed(resp.status()))
+    }
+
     /// List all projects associated with an organization
     pub fn list_organization_projects(&self, org: &str) -> ApiResult<Vec<Project>> {
         let mut rv = vec![];
@@ -1690,6 +1933,13 @@ impl ApiRequest {
         Ok(self)
     }
 
+    /// sets the raw request body for the request.
+    pub fn with_body(mut self, body: Vec<u8>) -> ApiResult<Self> {
+        debug!("raw body: {} bytes", body.len());
+        self.body = Some(body);
+        Ok(self)
+    }
+
     /// sets the JSON request body for the request.
     pub fn with_json_body<S: Serialize>(mut self, body: &S) -> ApiResult<Self> {
         let mut body_bytes: Vec<u8> = vec![];
@@ -2162,10 +2412,21 @@ pub struct DebugInfoData {
 /// Can be dSYMs, ELF debug infos, Breakpad symbols, etc...
 #[derive(Debug, Deserialize)]
 pub struct DebugInfoFile {
+    /// The identifier of the file on the server.
+    #[serde(rename = "id", default)]
+    pub file_id: Option<String>,
     #[serde(rename = "uuid")]
     uuid: Option<DebugId>,
     #[serde(rename = "debugId")]
     id: Option<DebugId>,
+    #[serde(rename = "codeId", default)]
+    pub code_id: Option<String>,
+    #[serde(rename = "symbolType", default)]
+    pub symbol_type: Option<String>,
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(rename = "dateCreated", default)]
+    pub date_created: Option<DateTime<Utc>>,
     #[serde(rename = "objectName")]
     pub object_name: String,
     #[serde(rename = "cpuName")]
@@ -2201,10 +2462,51 @@ struct MissingChecksumsResponse {
 /// Change information for issue bulk updates.
 #[derive(Serialize, Default)]
 pub struct IssueChanges {
-    #[serde(rename = "status")]
+    #[serde(rename = "status", skip_serializing_if = "Option::is_none")]
     pub new_status: Option<String>,
-    #[serde(rename = "snoozeDuration")]
+    #[serde(rename = "statusDetails", skip_serializing_if = "Option::is_none")]
+    pub status_details: Option<IssueStatusDetails>,
+    #[serde(rename = "snoozeDuration", skip_serializing_if = "Option::is_none")]
     pub snooze_duration: Option<i64>,
+    #[serde(rename = "assignedTo", skip_serializing_if = "Option::is_none")]
+    pub assigned_to: Option<String>,
+    #[serde(rename = "isBookmarked", skip_serializing_if = "Option::is_none")]
+    pub is_bookmarked: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge: Option<bool>,
+}
+
+/// Conditions under which a muted issue is unmuted again.
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueStatusDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_window: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_user_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_user_window: Option<u64>,
+}
+
+/// An issue as returned by the issue listing endpoints.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Issue {
+    pub id: String,
+    pub short_id: String,
+    pub title: String,
+    #[serde(default)]
+    pub culprit: Option<String>,
+    #[serde(default)]
+    pub permalink: Option<String>,
+    pub level: String,
+    pub status: String,
+    pub count: String,
+    pub user_count: u64,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
 }
 
 /// Filters for issue bulk requests.
@@ -2217,6 +2519,8 @@ pub enum IssueFilter {
     ExplicitIds(Vec<u64>),
     /// Match on issues with the given status
     Status(String),
+    /// Match on issues matching the given search query
+    Query(String),
 }
 
 impl IssueFilter {
@@ -2238,17 +2542,52 @@ impl IssueFilter {
             IssueFilter::Status(ref status) => {
                 rv.push(format!("status={}", status));
             }
+            IssueFilter::Query(ref query) => {
+                rv.push(format!("query={}", QueryArg(query)));
+            }
         }
         Some(rv.join("&"))
     }
 
+    /// Like `get_query_string`, but for listing issues.
+    ///
+    /// The list endpoint ignores `status` and searches `is:unresolved` by
+    /// default, so all filters are expressed as an explicit search query to
+    /// select the same issues as a bulk update.
+    fn get_search_query_string(&self) -> Option<String> {
+        match *self {
+            IssueFilter::All => Some("query=".into()),
+            IssueFilter::Status(ref status) => {
+                Some(format!("query={}", QueryArg(&format!("is:{}", status))))
+            }
+            IssueFilter::ExplicitIds(_) => {
+                self.get_query_string().map(|ids| format!("query=&{}", ids))
+            }
+            _ => self.get_query_string(),
+        }
+    }
+
     pub fn get_filter_from_matches(matches: &ArgMatches) -> Result<IssueFilter> {
+        // Global arguments given before and after the subcommand escape clap's
+        // conflict checks.
+        let selectors = ["all", "status", "query", "id"];
+        if selectors
+            .iter()
+            .filter(|&&arg| matches.is_present(arg))
+            .count()
+            > 1
+        {
+            bail!("Only one of --all, --status, --query and --id can be used.");
+        }
         if matches.is_present("all") {
             return Ok(IssueFilter::All);
         }
         if let Some(status) = matches.value_of("status") {
             return Ok(IssueFilter::Status(status.into()));
         }
+        if let Some(query) = matches.value_of("query") {
+            return Ok(IssueFilter::Query(query.into()));
+        }
         let mut ids = vec![];
         if let Some(values) = matches.values_of("id") {
             for value in values {
@@ -2310,8 +2649,125 @@ pub struct Project {
 #[derive(Debug, Deserialize)]
 pub struct Monitor {
     pub id: String,
+    #[serde(default)]
+    pub slug: Option<String>,
     pub name: String,
     pub status: String,
+    #[serde(default)]
+    pub config: MonitorConfig,
+    #[serde(default, rename = "nextCheckIn")]
+    pub next_checkin: Option<DateTime<Utc>>,
+    #[serde(default, rename = "lastCheckIn")]
+    pub last_checkin: Option<DateTime<Utc>>,
+}
+
+impl Monitor {
+    /// Returns the slug of this monitor, falling back to its name for
+    /// servers that do not know about monitor slugs.
+    pub fn slug(&self) -> &str {
+        self.slug.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorScheduleType {
+    Crontab,
+    Interval,
+}
+
+/// The schedule of a monitor, either a crontab expression or an interval
+/// given as a number and a unit (e.g. `[1, "hour"]`).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum MonitorSchedule {
+    Crontab(String),
+    Interval(u64, String),
+}
+
+impl MonitorSchedule {
+    pub fn schedule_type(&self) -> MonitorScheduleType {
+        match *self {
+            MonitorSchedule::Crontab(..) => MonitorScheduleType::Crontab,
+            MonitorSchedule::Interval(..) => MonitorScheduleType::Interval,
+        }
+    }
+}
+
+impl fmt::Display for MonitorSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            MonitorSchedule::Crontab(ref crontab) => write!(f, "{}", crontab),
+            MonitorSchedule::Interval(1, ref unit) => write!(f, "every {}", unit),
+            MonitorSchedule::Interval(n, ref unit) => write!(f, "every {} {}s", n, unit),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct MonitorConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule_type: Option<MonitorScheduleType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<MonitorSchedule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkin_margin: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_runtime: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateMonitor {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub config: MonitorConfig,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct UpdateMonitor {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<MonitorConfig>,
+}
+
+/// Returns the longest retry delay in seconds from an
+/// `X-Sentry-Rate-Limits` header.
+fn parse_rate_limits(header: &str) -> Option<u64> {
+    header
+        .split(',')
+        .filter_map(|limit| limit.trim().split(':').next()?.parse::<u64>().ok())
+        .max()
+}
+
+/// The outcome of sending an envelope to Sentry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvelopeResponse {
+    /// The envelope was accepted.
+    Accepted,
+    /// Sentry is rate limiting and asked to retry after the given seconds.
+    RateLimited(u64),
+    /// The envelope was not accepted and the server responded with the
+    /// given status code.
+    Failed(u32),
+}
+
+impl EnvelopeResponse {
+    /// Returns `true` if sending the envelope again later might succeed.
+    pub fn is_retryable(self) -> bool {
+        match self {
+            EnvelopeResponse::Accepted => false,
+            EnvelopeResponse::RateLimited(_) => true,
+            EnvelopeResponse::Failed(status) => status >= 500,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -2321,6 +2777,7 @@ pub enum MonitorStatus {
     Ok,
     InProgress,
     Error,
+    Timeout,
 }
 
 #[derive(Debug, Deserialize)]
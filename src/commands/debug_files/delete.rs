@@ -0,0 +1,66 @@
+use anyhow::{bail, Result};
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::Api;
+use crate::commands::debug_files::list::{filter_args, has_filters, print_difs, select_difs};
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+use crate::utils::ui::prompt_to_continue;
+
+pub fn make_command(command: Command) -> Command {
+    filter_args(
+        command
+            .about("Delete debug information files from the server.")
+            .org_arg()
+            .project_arg(false)
+            .arg(
+                Arg::new("file_ids")
+                    .value_name("FILE_ID")
+                    .multiple_occurrences(true)
+                    .help("The IDs of the files on the server, as shown by `debug-files list`."),
+            )
+            .arg(
+                Arg::new("yes")
+                    .long("yes")
+                    .short('y')
+                    .help("Skip the confirmation prompt."),
+            ),
+    )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+    let file_ids = matches.values_of("file_ids");
+    if file_ids.is_none() && !has_filters(matches) {
+        bail!("Select the files to delete by file ID or filter.");
+    }
+
+    let difs = select_difs(matches, &org, &project, file_ids)?;
+    if difs.is_empty() {
+        println!("No debug information files found.");
+        return Ok(());
+    }
+
+    print_difs(&difs);
+    if !matches.is_present("yes")
+        && !prompt_to_continue(&format!(
+            "Do you really want to delete {} debug information files?",
+            difs.len()
+        ))?
+    {
+        println!("Aborted!");
+        return Ok(());
+    }
+
+    let api = Api::current();
+    let mut deleted = 0;
+    for file_id in difs.iter().filter_map(|dif| dif.file_id.as_deref()) {
+        if api.delete_dif(&org, &project, file_id)? {
+            deleted += 1;
+        }
+    }
+
+    println!("Deleted {} debug information files.", deleted);
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+
+use crate::api::Api;
+use crate::commands::debug_files::list::{filter_args, has_filters, select_difs};
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+
+pub fn make_command(command: Command) -> Command {
+    filter_args(
+        command
+            .about("Download debug information files from the server.")
+            .org_arg()
+            .project_arg(false)
+            .arg(
+                Arg::new("file_ids")
+                    .value_name("FILE_ID")
+                    .multiple_occurrences(true)
+                    .help("The IDs of the files on the server, as shown by `debug-files list`."),
+            )
+            .arg(
+                Arg::new("output")
+                    .long("output")
+                    .value_name("DIR")
+                    .default_value(".")
+                    .help("The directory to download the files into."),
+            ),
+    )
+}
+
+/// Returns the path for a downloaded file, which is the object name in a
+/// directory named after the debug ID.
+fn output_path(
+    output: &Path,
+    debug_id: &str,
+    object_name: &str,
+    file_id: &str,
+    used: &mut HashSet<PathBuf>,
+) -> PathBuf {
+    let name = Path::new(object_name)
+        .file_name()
+        .map_or_else(|| file_id.into(), |name| name.to_string_lossy());
+    let path = output.join(debug_id).join(name.as_ref());
+    // Executables and their debug companions usually share the debug ID and
+    // the object name.
+    if used.insert(path.clone()) {
+        path
+    } else {
+        let path = output.join(debug_id).join(format!("{}.{}", name, file_id));
+        used.insert(path.clone());
+        path
+    }
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+    let file_ids = matches.values_of("file_ids");
+    if file_ids.is_none() && !has_filters(matches) {
+        bail!("Select the files to download by file ID or filter.");
+    }
+
+    let difs = select_difs(matches, &org, &project, file_ids)?;
+    if difs.is_empty() {
+        println!("No debug information files found.");
+        return Ok(());
+    }
+
+    let api = Api::current();
+    let output = Path::new(matches.value_of("output").unwrap());
+    let mut used = HashSet::new();
+    for dif in &difs {
+        let file_id = match dif.file_id {
+            Some(ref file_id) => file_id,
+            None => continue,
+        };
+        let path = output_path(
+            output,
+            &dif.id().to_string(),
+            &dif.object_name,
+            file_id,
+            &mut used,
+        );
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = File::create(&path)?;
+        if let Err(err) = api.download_dif(&org, &project, file_id, &mut file) {
+            fs::remove_file(&path).ok();
+            return Err(err.into());
+        }
+        println!(
+            "  {} {} -> {}",
+            style(">").dim(),
+            dif.object_name,
+            path.display()
+        );
+    }
+
+    println!("Downloaded {} debug information files.", difs.len());
+    Ok(())
+}
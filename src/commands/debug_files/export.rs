@@ -0,0 +1,111 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+use symbolic::common::DebugId;
+use symbolic::debuginfo::FileFormat;
+
+use crate::utils::args::validate_id;
+use crate::utils::dif_layout::DifLayout;
+use crate::utils::dif_upload::{DifFormat, DifUpload};
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Copy debug information files into a symbol server directory layout.")
+        .arg(
+            Arg::new("paths")
+                .value_name("PATH")
+                .help("A path to search recursively for symbol files.")
+                .required(true)
+                .multiple_occurrences(true),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .value_name("DIR")
+                .required(true)
+                .help("The root directory of the symbol server layout."),
+        )
+        .arg(
+            Arg::new("layout")
+                .long("layout")
+                .value_name("LAYOUT")
+                .possible_values(DifLayout::NAMES)
+                .default_value("unified")
+                .help("The directory layout to use."),
+        )
+        .arg(
+            Arg::new("hardlink")
+                .long("hardlink")
+                .help("Link files into the layout instead of copying them, where possible."),
+        )
+        .arg(
+            Arg::new("types")
+                .long("type")
+                .short('t')
+                .value_name("TYPE")
+                .multiple_occurrences(true)
+                .possible_values(["dsym", "elf", "breakpad", "pdb", "pe", "sourcebundle"])
+                .help(
+                    "Only consider debug information files of the given \
+                    type.  By default, all types are considered.",
+                ),
+        )
+        .arg(
+            Arg::new("ids")
+                .value_name("ID")
+                .long("id")
+                .help("Search for specific debug identifiers.")
+                .validator(validate_id)
+                .multiple_occurrences(true),
+        )
+        .arg(
+            Arg::new("no_zips")
+                .long("no-zips")
+                .help("Do not search in ZIP files."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let ids = matches
+        .values_of("ids")
+        .unwrap_or_default()
+        .filter_map(|s| DebugId::from_str(s).ok());
+
+    // Exports do not talk to a server, so there is no org or project.
+    let mut export = DifUpload::new(String::new(), String::new());
+    export
+        .search_paths(matches.values_of("paths").unwrap_or_default())
+        .allow_zips(!matches.is_present("no_zips"))
+        .filter_ids(ids);
+
+    for ty in matches.values_of("types").unwrap_or_default() {
+        match ty {
+            "dsym" => export.filter_format(DifFormat::Object(FileFormat::MachO)),
+            "elf" => export.filter_format(DifFormat::Object(FileFormat::Elf)),
+            "breakpad" => export.filter_format(DifFormat::Object(FileFormat::Breakpad)),
+            "pdb" => export.filter_format(DifFormat::Object(FileFormat::Pdb)),
+            "pe" => export.filter_format(DifFormat::Object(FileFormat::Pe)),
+            "sourcebundle" => export.filter_format(DifFormat::Object(FileFormat::SourceBundle)),
+            other => bail!("Unsupported type: {}", other),
+        };
+    }
+
+    let output = Path::new(matches.value_of("output").unwrap());
+    let layout = matches.value_of("layout").unwrap().parse::<DifLayout>()?;
+    let paths = export.export(output, layout, matches.is_present("hardlink"))?;
+
+    for path in &paths {
+        println!("  {}", path);
+    }
+    println!(
+        "{} Exported {} files into {} ({} layout)",
+        style(">").dim(),
+        style(paths.len()).yellow(),
+        output.display(),
+        layout
+    );
+    Ok(())
+}
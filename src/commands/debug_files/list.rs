@@ -0,0 +1,221 @@
+use anyhow::{Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use glob::Pattern;
+use indicatif::HumanBytes;
+use symbolic::common::DebugId;
+
+use crate::api::{Api, DebugInfoFile};
+use crate::config::Config;
+use crate::utils::args::{get_timestamp, validate_id, validate_timestamp, ArgExt};
+use crate::utils::formatting::Table;
+
+/// The types of debug information files known to the server.
+const DIF_TYPES: &[&str] = &[
+    "dsym",
+    "elf",
+    "pe",
+    "pdb",
+    "portablepdb",
+    "wasm",
+    "breakpad",
+    "sourcebundle",
+    "bcsymbolmap",
+    "uuidmap",
+    "il2cpp",
+];
+
+pub fn make_command(command: Command) -> Command {
+    filter_args(
+        command
+            .about("List debug information files stored on the server.")
+            .org_arg()
+            .project_arg(false),
+    )
+}
+
+/// Adds the arguments that select debug information files on the server,
+/// shared with the commands that download or delete them.
+pub fn filter_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("debug_id")
+                .long("id")
+                .value_name("DEBUG_ID")
+                .validator(validate_id)
+                .help("Only select files with the given debug identifier."),
+        )
+        .arg(
+            Arg::new("code_id")
+                .long("code-id")
+                .value_name("CODE_ID")
+                .help("Only select files with the given code identifier."),
+        )
+        .arg(
+            Arg::new("types")
+                .long("type")
+                .short('t')
+                .value_name("TYPE")
+                .multiple_occurrences(true)
+                .possible_values(DIF_TYPES)
+                .help("Only select files of the given type."),
+        )
+        .arg(
+            Arg::new("name")
+                .long("name")
+                .value_name("GLOB")
+                .help("Only select files whose object name matches the glob."),
+        )
+        .arg(
+            Arg::new("uploaded_after")
+                .long("uploaded-after")
+                .value_name("TIMESTAMP")
+                .validator(validate_timestamp)
+                .help("Only select files uploaded after the given timestamp."),
+        )
+        .arg(
+            Arg::new("uploaded_before")
+                .long("uploaded-before")
+                .value_name("TIMESTAMP")
+                .validator(validate_timestamp)
+                .help("Only select files uploaded before the given timestamp."),
+        )
+}
+
+/// Returns whether any of the `filter_args` is given.
+pub fn has_filters(matches: &ArgMatches) -> bool {
+    [
+        "debug_id",
+        "code_id",
+        "types",
+        "name",
+        "uploaded_after",
+        "uploaded_before",
+    ]
+    .iter()
+    .any(|arg| matches.is_present(arg))
+}
+
+/// Converts a type argument into the symbol type of the server.
+fn symbol_type(ty: &str) -> &str {
+    match ty {
+        "dsym" => "macho",
+        other => other,
+    }
+}
+
+/// Lists the debug information files on the server that match the
+/// `filter_args` and, if given, have one of the file IDs.
+pub fn select_difs<'a, I>(
+    matches: &ArgMatches,
+    org: &str,
+    project: &str,
+    file_ids: Option<I>,
+) -> Result<Vec<DebugInfoFile>>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let debug_id = matches
+        .value_of("debug_id")
+        .map(|id| id.parse::<DebugId>())
+        .transpose()?;
+    let code_id = matches.value_of("code_id").map(str::to_lowercase);
+    let types: Vec<_> = matches
+        .values_of("types")
+        .map(|types| types.map(symbol_type).collect())
+        .unwrap_or_default();
+    let name = matches
+        .value_of("name")
+        .map(|name| Pattern::new(name).with_context(|| format!("Invalid glob pattern {}", name)))
+        .transpose()?;
+    let uploaded_after = matches
+        .value_of("uploaded_after")
+        .map(get_timestamp)
+        .transpose()?;
+    let uploaded_before = matches
+        .value_of("uploaded_before")
+        .map(get_timestamp)
+        .transpose()?;
+    let file_ids: Option<Vec<_>> = file_ids.map(|ids| ids.into_iter().collect());
+
+    // The server searches debug and code identifiers, the remaining filters
+    // are applied to the listing.
+    let query = debug_id
+        .map(|id| id.to_string())
+        .or_else(|| code_id.clone());
+    let difs = Api::current().list_difs(org, project, query.as_deref(), &types)?;
+
+    Ok(difs
+        .into_iter()
+        .filter(|dif| debug_id.is_none() || debug_id == Some(dif.id()))
+        .filter(|dif| {
+            code_id.is_none() || dif.code_id.as_deref().map(str::to_lowercase) == code_id
+        })
+        .filter(|dif| {
+            types.is_empty()
+                || matches!(&dif.symbol_type, Some(ty) if types.contains(&ty.as_str()))
+        })
+        .filter(|dif| match name {
+            Some(ref pattern) => pattern.matches(&dif.object_name),
+            None => true,
+        })
+        .filter(|dif| match (uploaded_after, dif.date_created) {
+            (Some(after), Some(date)) => date > after,
+            (Some(_), None) => false,
+            (None, _) => true,
+        })
+        .filter(|dif| match (uploaded_before, dif.date_created) {
+            (Some(before), Some(date)) => date < before,
+            (Some(_), None) => false,
+            (None, _) => true,
+        })
+        .filter(|dif| match (&file_ids, &dif.file_id) {
+            (Some(ids), Some(file_id)) => ids.contains(&file_id.as_str()),
+            (Some(_), None) => false,
+            (None, _) => true,
+        })
+        .collect())
+}
+
+/// Prints a table of debug information files.
+pub fn print_difs(difs: &[DebugInfoFile]) {
+    let mut table = Table::new();
+    table
+        .title_row()
+        .add("ID")
+        .add("Debug ID")
+        .add("Type")
+        .add("Name")
+        .add("Arch")
+        .add("Size")
+        .add("Uploaded");
+
+    for dif in difs {
+        table
+            .add_row()
+            .add(dif.file_id.as_deref().unwrap_or(""))
+            .add(dif.id())
+            .add(dif.symbol_type.as_deref().unwrap_or(""))
+            .add(&dif.object_name)
+            .add(&dif.cpu_name)
+            .add(dif.size.map(HumanBytes).map_or(String::new(), |s| s.to_string()))
+            .add(
+                dif.date_created
+                    .map_or(String::new(), |date| date.format("%F").to_string()),
+            );
+    }
+
+    table.print();
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+
+    let difs = select_difs(matches, &org, &project, None::<Vec<&str>>)?;
+    if difs.is_empty() {
+        println!("No debug information files found.");
+        return Ok(());
+    }
+    print_difs(&difs);
+    Ok(())
+}
@@ -3,14 +3,26 @@ use clap::{ArgMatches, Command};
 
 pub mod bundle_sources;
 pub mod check;
+pub mod delete;
+pub mod download;
+pub mod export;
 pub mod find;
+pub mod list;
+pub mod prune;
+pub mod serve;
 pub mod upload;
 
 macro_rules! each_subcommand {
     ($mac:ident) => {
         $mac!(bundle_sources);
         $mac!(check);
+        $mac!(delete);
+        $mac!(download);
+        $mac!(export);
         $mac!(find);
+        $mac!(list);
+        $mac!(prune);
+        $mac!(serve);
         $mac!(upload);
     };
 }
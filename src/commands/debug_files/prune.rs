@@ -0,0 +1,267 @@
+use std::cmp::Reverse;
+
+use anyhow::{format_err, Result};
+use chrono::{DateTime, Duration, Utc};
+use clap::{Arg, ArgGroup, ArgMatches, Command};
+use indicatif::HumanBytes;
+
+use crate::api::{Api, DebugInfoFile, ReleaseInfo};
+use crate::commands::debug_files::list::{filter_args, select_difs};
+use crate::config::Config;
+use crate::utils::args::{get_duration, validate_duration, ArgExt};
+use crate::utils::formatting::Table;
+use crate::utils::ui::prompt_to_continue;
+
+pub fn make_command(command: Command) -> Command {
+    filter_args(
+        command
+            .about("Delete debug information files that are no longer needed.")
+            .org_arg()
+            .project_arg(false)
+            .arg(
+                Arg::new("keep_releases")
+                    .long("keep-releases")
+                    .value_name("N")
+                    .validator(validate_keep_releases)
+                    .help(
+                        "Keep the files of the N most recent releases.  Sentry does not \
+                         record which release a file belongs to, so a file is counted toward \
+                         the newest release created before the end of the --release-grace \
+                         period after its upload.  Files uploaded earlier than that before \
+                         their release was created are counted toward the previous release.",
+                    ),
+            )
+            .arg(
+                Arg::new("release_grace")
+                    .long("release-grace")
+                    .value_name("DURATION")
+                    .validator(validate_duration)
+                    .default_value("1h")
+                    .requires("keep_releases")
+                    .help(
+                        "How long after uploading a file its release may be created, \
+                         for builds that upload files before creating the release.",
+                    ),
+            )
+            .arg(
+                Arg::new("older_than")
+                    .long("older-than")
+                    .value_name("DURATION")
+                    .validator(validate_duration)
+                    .help(
+                        "Only delete files uploaded longer ago than the given duration, \
+                         for example 90d.  Supported units are s, m, h, d and w.",
+                    ),
+            )
+            .group(
+                ArgGroup::new("policy")
+                    .args(&["keep_releases", "older_than"])
+                    .multiple(true)
+                    .required(true),
+            )
+            .arg(
+                Arg::new("dry_run")
+                    .long("dry-run")
+                    .help("Print the files that would be deleted without deleting them."),
+            )
+            .arg(
+                Arg::new("yes")
+                    .long("yes")
+                    .short('y')
+                    .help("Skip the confirmation prompt."),
+            ),
+    )
+}
+
+fn validate_keep_releases(v: &str) -> Result<(), String> {
+    match v.parse::<usize>() {
+        Ok(0) => Err("At least one release must be kept.".to_string()),
+        Ok(_) => Ok(()),
+        Err(_) => Err("Invalid number, positive integer required.".to_string()),
+    }
+}
+
+/// Whether a file is kept or deleted, and why.
+enum Decision {
+    Keep(String),
+    Delete(String),
+}
+
+/// Returns the release a file most likely belongs to: the newest release
+/// created before the end of the grace period after the upload.
+///
+/// This is a heuristic, since releases do not reference debug files.
+fn release_at(
+    releases: &[ReleaseInfo],
+    uploaded: DateTime<Utc>,
+    grace: Duration,
+) -> Option<&ReleaseInfo> {
+    let latest = uploaded.checked_add_signed(grace).unwrap_or(uploaded);
+    releases
+        .iter()
+        .filter(|release| release.date_created <= latest)
+        .max_by_key(|release| release.date_created)
+}
+
+/// Decides whether to delete a file.
+///
+/// Files of the `recent_releases` newest `releases`, which are sorted by
+/// creation date, are kept, allowing for releases created up to `grace`
+/// after the upload. `cutoff` is the upload date before which files
+/// are deleted, along with the age it was computed from.
+fn decide(
+    dif: &DebugInfoFile,
+    releases: &[ReleaseInfo],
+    recent_releases: Option<usize>,
+    grace: Duration,
+    cutoff: Option<(DateTime<Utc>, &str)>,
+) -> Decision {
+    let uploaded = match dif.date_created {
+        Some(uploaded) => uploaded,
+        None => return Decision::Keep("unknown upload date".into()),
+    };
+
+    if let Some(n) = recent_releases {
+        if let Some(release) = release_at(releases, uploaded, grace) {
+            if releases
+                .iter()
+                .take(n)
+                .any(|recent| recent.version == release.version)
+            {
+                return Decision::Keep(format!("release {}", release.version));
+            }
+        }
+    }
+
+    match (recent_releases, cutoff) {
+        (_, Some((cutoff, age))) if uploaded > cutoff => {
+            Decision::Keep(format!("newer than {}", age))
+        }
+        (Some(n), Some((_, age))) => {
+            Decision::Delete(format!("not in last {} releases, older than {}", n, age))
+        }
+        (Some(n), None) => Decision::Delete(format!("not in last {} releases", n)),
+        (None, Some((_, age))) => Decision::Delete(format!("older than {}", age)),
+        (None, None) => Decision::Keep(String::new()),
+    }
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+    let api = Api::current();
+
+    let keep_releases = matches
+        .value_of("keep_releases")
+        .map(str::parse::<usize>)
+        .transpose()?;
+    let cutoff = match matches.value_of("older_than") {
+        Some(age) => {
+            let cutoff = Utc::now()
+                .checked_sub_signed(get_duration(age)?)
+                .ok_or_else(|| format_err!("Invalid duration {}. The duration is too long.", age))?;
+            Some((cutoff, age))
+        }
+        None => None,
+    };
+    let grace = get_duration(matches.value_of("release_grace").unwrap())?;
+    let releases = match keep_releases {
+        Some(_) => {
+            let mut releases = api.list_all_releases(&org, &project)?;
+            releases.sort_by_key(|release| Reverse(release.date_created));
+            releases
+        }
+        None => vec![],
+    };
+
+    let difs = select_difs(matches, &org, &project, None::<Vec<&str>>)?;
+    if difs.is_empty() {
+        println!("No debug information files found.");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .title_row()
+        .add("ID")
+        .add("Debug ID")
+        .add("Name")
+        .add("Size")
+        .add("Uploaded")
+        .add("Action")
+        .add("Reason");
+
+    let mut to_delete = vec![];
+    let mut delete_size = 0;
+    for dif in &difs {
+        let (action, reason) = match decide(dif, &releases, keep_releases, grace, cutoff) {
+            Decision::Keep(reason) => ("keep", reason),
+            Decision::Delete(reason) => {
+                to_delete.push(dif);
+                delete_size += dif.size.unwrap_or(0);
+                ("delete", reason)
+            }
+        };
+        table
+            .add_row()
+            .add(dif.file_id.as_deref().unwrap_or(""))
+            .add(dif.id())
+            .add(&dif.object_name)
+            .add(dif.size.map(HumanBytes).map_or(String::new(), |s| s.to_string()))
+            .add(
+                dif.date_created
+                    .map_or(String::new(), |date| date.format("%F").to_string()),
+            )
+            .add(action)
+            .add(reason);
+    }
+    table.print();
+
+    println!(
+        "{} of {} debug information files ({}) will be deleted.",
+        to_delete.len(),
+        difs.len(),
+        HumanBytes(delete_size)
+    );
+    if to_delete.is_empty() || matches.is_present("dry_run") {
+        return Ok(());
+    }
+
+    if !matches.is_present("yes")
+        && !prompt_to_continue(&format!(
+            "Do you really want to delete {} debug information files?",
+            to_delete.len()
+        ))?
+    {
+        println!("Aborted!");
+        return Ok(());
+    }
+
+    let mut deleted = 0;
+    for file_id in to_delete.iter().filter_map(|dif| dif.file_id.as_deref()) {
+        if api.delete_dif(&org, &project, file_id)? {
+            deleted += 1;
+        }
+    }
+
+    println!("Deleted {} debug information files.", deleted);
+    Ok(())
+}
+
+#[test]
+fn test_release_at_grace() {
+    let releases: Vec<ReleaseInfo> = serde_json::from_str(
+        r#"[
+            {"version": "app@1.1.0", "dateCreated": "2022-06-15T10:05:00Z"},
+            {"version": "app@1.0.0", "dateCreated": "2022-04-01T10:00:00Z"}
+        ]"#,
+    )
+    .unwrap();
+    // Builds commonly upload their files right before creating the release.
+    let uploaded = "2022-06-15T10:00:00Z".parse().unwrap();
+
+    let release = release_at(&releases, uploaded, Duration::hours(1)).unwrap();
+    assert_eq!(release.version, "app@1.1.0");
+    let release = release_at(&releases, uploaded, Duration::zero()).unwrap();
+    assert_eq!(release.version, "app@1.0.0");
+}
@@ -0,0 +1,53 @@
+use std::net::TcpListener;
+
+use anyhow::{Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+
+use crate::utils::dif_layout::DifLayout;
+use crate::utils::symbol_server::{serve, SymbolIndex};
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Serve debug information files from local directories over HTTP.")
+        .arg(
+            Arg::new("paths")
+                .long("path")
+                .value_name("PATH")
+                .required(true)
+                .multiple_occurrences(true)
+                .help("A directory to search recursively for debug information files."),
+        )
+        .arg(
+            Arg::new("bind")
+                .long("bind")
+                .value_name("ADDRESS")
+                .default_value("127.0.0.1:8000")
+                .help("The address to listen on."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let index = SymbolIndex::build(matches.values_of("paths").unwrap())?;
+    println!(
+        "{} Found {} debug information files",
+        style(">").dim(),
+        style(index.files()).yellow()
+    );
+
+    let bind = matches.value_of("bind").unwrap();
+    let listener =
+        TcpListener::bind(bind).with_context(|| format!("Could not listen on {}", bind))?;
+    let layouts: Vec<_> = DifLayout::all()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    println!(
+        "{} Serving the {} layouts on http://{}/",
+        style(">").dim(),
+        layouts.join(", "),
+        listener.local_addr()?
+    );
+
+    serve(listener, index)
+}
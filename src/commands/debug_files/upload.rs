@@ -167,6 +167,16 @@ pub fn make_command(command: Command) -> Command {
             "Compute il2cpp line mappings and upload \
             them along with sources.",
         ))
+        .arg(
+            Arg::new("manifest")
+                .long("manifest")
+                .value_name("PATH")
+                .help(
+                    "Record the progress of the upload in a JSON file.  Running \
+                    the upload again with the same manifest skips hashing unchanged \
+                    files and files that were already uploaded.",
+                ),
+        )
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -224,6 +234,10 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     upload.include_sources(matches.is_present("include_sources"));
     upload.il2cpp_mapping(matches.is_present("il2cpp_mapping"));
 
+    if let Some(manifest) = matches.value_of("manifest") {
+        upload.manifest(manifest);
+    }
+
     // Configure BCSymbolMap resolution, if possible
     if let Some(symbol_map) = matches.value_of("symbol_maps") {
         upload
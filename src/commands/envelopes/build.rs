@@ -0,0 +1,130 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgGroup, ArgMatches, Command};
+use sentry::protocol::{Attachment, EnvelopeItem, Event, SessionUpdate, Transaction};
+use sentry::Envelope;
+use serde::de::DeserializeOwned;
+
+use crate::utils::event::serialize_envelope;
+
+/// The arguments that add items to the envelope, in no particular order.
+const ITEM_ARGS: &[&str] = &["event", "transaction", "session", "attachment"];
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Build an envelope from event, transaction, session and attachment files.")
+        .long_about(
+            "Build an envelope from event, transaction, session and attachment files.{n}{n}\
+             Items are added to the envelope in the order they are given on the command line. \
+             Events, transactions and sessions are read from JSON files and validated, \
+             attachments are added as they are.",
+        )
+        .arg(
+            Arg::new("event")
+                .long("event")
+                .value_name("PATH")
+                .multiple_occurrences(true)
+                .help("Add an event from a JSON file."),
+        )
+        .arg(
+            Arg::new("transaction")
+                .long("transaction")
+                .value_name("PATH")
+                .multiple_occurrences(true)
+                .help("Add a transaction from a JSON file."),
+        )
+        .arg(
+            Arg::new("session")
+                .long("session")
+                .value_name("PATH")
+                .multiple_occurrences(true)
+                .help("Add a session update from a JSON file."),
+        )
+        .arg(
+            Arg::new("attachment")
+                .long("attachment")
+                .value_name("PATH")
+                .multiple_occurrences(true)
+                .help("Add a file as an attachment."),
+        )
+        .group(
+            ArgGroup::new("items")
+                .args(ITEM_ARGS)
+                .multiple(true)
+                .required(true),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .short('o')
+                .value_name("PATH")
+                .help("Write the envelope to the given file instead of stdout."),
+        )
+}
+
+fn read_json<T: DeserializeOwned>(path: &str, what: &str) -> Result<T> {
+    let file = fs::File::open(path).with_context(|| format!("Could not open {}", path))?;
+    serde_json::from_reader(io::BufReader::new(file))
+        .with_context(|| format!("Could not parse {} from {}", what, path))
+}
+
+fn read_item(kind: &str, path: &str) -> Result<EnvelopeItem> {
+    Ok(match kind {
+        "event" => read_json::<Event<'static>>(path, "event")?.into(),
+        "transaction" => read_json::<Transaction<'static>>(path, "transaction")?.into(),
+        "session" => read_json::<SessionUpdate<'static>>(path, "session")?.into(),
+        "attachment" => Attachment {
+            buffer: fs::read(path).with_context(|| format!("Could not read {}", path))?,
+            filename: Path::new(path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+        .into(),
+        _ => unreachable!(),
+    })
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let mut inputs = vec![];
+    for &kind in ITEM_ARGS {
+        if let (Some(indices), Some(paths)) = (matches.indices_of(kind), matches.values_of(kind)) {
+            inputs.extend(indices.zip(paths).map(|(index, path)| (index, kind, path)));
+        }
+    }
+    inputs.sort_by_key(|&(index, _, _)| index);
+
+    let events = inputs
+        .iter()
+        .filter(|(_, kind, _)| *kind == "event" || *kind == "transaction")
+        .count();
+    if events > 1 {
+        bail!("An envelope can contain at most one event or transaction.");
+    }
+
+    let mut envelope = Envelope::new();
+    for &(_, kind, path) in &inputs {
+        envelope.add_item(read_item(kind, path)?);
+    }
+    let bytes = serialize_envelope(&envelope)?;
+
+    if let Some(output) = matches.value_of("output") {
+        fs::write(output, &bytes).with_context(|| format!("Could not write {}", output))?;
+        println!(
+            "Wrote envelope with {} item(s) to {}",
+            inputs.len(),
+            output
+        );
+    } else {
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        stdout.write_all(&bytes)?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
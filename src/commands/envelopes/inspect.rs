@@ -0,0 +1,142 @@
+use std::convert::TryFrom;
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+use sentry::Envelope;
+use serde_json::Value;
+
+use crate::utils::system::QuietExit;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Print the headers and sizes of the items in an envelope and validate them.")
+        .arg(
+            Arg::new("path")
+                .value_name("PATH")
+                .required(true)
+                .help("The path to the file in envelope format."),
+        )
+}
+
+/// A single item of an envelope as found in the raw file.
+struct RawItem<'a> {
+    header: Value,
+    /// The header line and payload, as they appear in the envelope.
+    bytes: &'a [u8],
+    payload_len: usize,
+}
+
+/// Reads a JSON header at `offset` and returns it with the offset right
+/// after its terminating newline.
+fn read_header(data: &[u8], offset: usize) -> Result<(Value, usize)> {
+    let mut stream = serde_json::Deserializer::from_slice(&data[offset..]).into_iter::<Value>();
+    let header = match stream.next() {
+        Some(header) => header?,
+        None => bail!("unexpected end of file"),
+    };
+    let end = offset + stream.byte_offset();
+    match data.get(end) {
+        Some(b'\n') => Ok((header, end + 1)),
+        None => Ok((header, end)),
+        Some(_) => bail!("missing newline after header"),
+    }
+}
+
+/// Splits the envelope into its header and items without interpreting the
+/// item payloads.
+fn split_envelope(data: &[u8]) -> Result<(Value, Vec<RawItem<'_>>)> {
+    let (header, mut offset) = read_header(data, 0).context("invalid envelope header")?;
+    let mut items = vec![];
+
+    while offset < data.len() {
+        let start = offset;
+        let (item_header, payload_start) = read_header(data, offset)
+            .with_context(|| format!("invalid header of item {}", items.len() + 1))?;
+
+        let payload_end = match item_header.get("length").and_then(Value::as_u64) {
+            Some(length) => match usize::try_from(length)
+                .ok()
+                .and_then(|length| payload_start.checked_add(length))
+            {
+                Some(end) if end <= data.len() => end,
+                _ => bail!(
+                    "item {} declares a length past the end of the envelope",
+                    items.len() + 1
+                ),
+            },
+            None => data[payload_start..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(data.len(), |pos| payload_start + pos),
+        };
+
+        offset = match data.get(payload_end) {
+            Some(b'\n') => payload_end + 1,
+            None => payload_end,
+            Some(_) => bail!("missing newline after payload of item {}", items.len() + 1),
+        };
+
+        items.push(RawItem {
+            header: item_header,
+            bytes: &data[start..offset],
+            payload_len: payload_end - payload_start,
+        });
+    }
+
+    Ok((header, items))
+}
+
+/// Validates a single item by parsing it as an envelope of its own.
+fn validate_item(item: &RawItem<'_>) -> Result<()> {
+    let mut single = b"{}\n".to_vec();
+    single.extend_from_slice(item.bytes);
+    Envelope::from_slice(&single)?;
+    Ok(())
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let path = matches.value_of("path").unwrap();
+    let data = fs::read(path).with_context(|| format!("Could not read {}", path))?;
+
+    let (header, items) = match split_envelope(&data) {
+        Ok(rv) => rv,
+        Err(err) => {
+            println!("{} {:#}", style("error:").red(), err);
+            return Err(QuietExit(1).into());
+        }
+    };
+
+    println!("Envelope: {} ({} bytes)", path, data.len());
+    println!("  header: {}", header);
+
+    let mut valid = true;
+    for (index, item) in items.iter().enumerate() {
+        let ty = item
+            .header
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        println!();
+        println!("Item {}: {} ({} bytes)", index + 1, ty, item.payload_len);
+        println!("  header: {}", item.header);
+        match validate_item(item) {
+            Ok(()) => println!("  {}", style("valid").green()),
+            Err(err) => {
+                valid = false;
+                println!("  {} {:#}", style("error:").red(), err);
+            }
+        }
+    }
+
+    if items.is_empty() {
+        println!("  no items");
+    }
+
+    if valid {
+        Ok(())
+    } else {
+        Err(QuietExit(1).into())
+    }
+}
@@ -0,0 +1,44 @@
+use anyhow::Result;
+use clap::{ArgMatches, Command};
+
+pub mod build;
+pub mod inspect;
+
+macro_rules! each_subcommand {
+    ($mac:ident) => {
+        $mac!(build);
+        $mac!(inspect);
+    };
+}
+
+pub fn make_command(mut command: Command) -> Command {
+    macro_rules! add_subcommand {
+        ($name:ident) => {{
+            command = command.subcommand(crate::commands::envelopes::$name::make_command(
+                Command::new(stringify!($name).replace('_', "-")),
+            ));
+        }};
+    }
+
+    command = command
+        .about("Build and inspect envelopes.")
+        .subcommand_required(true)
+        .arg_required_else_help(true);
+
+    each_subcommand!(add_subcommand);
+    command
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    macro_rules! execute_subcommand {
+        ($name:ident) => {{
+            if let Some(sub_matches) =
+                matches.subcommand_matches(&stringify!($name).replace('_', "-"))
+            {
+                return crate::commands::envelopes::$name::execute(&sub_matches);
+            }
+        }};
+    }
+    each_subcommand!(execute_subcommand);
+    unreachable!();
+}
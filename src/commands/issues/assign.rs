@@ -0,0 +1,37 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::IssueChanges;
+use crate::utils::issues::update_selected_issues;
+
+use super::dry_run_arg;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Bulk assign all selected issues to a user or team.")
+        .arg(
+            Arg::new("to")
+                .long("to")
+                .value_name("ASSIGNEE")
+                .required_unless_present("unassign")
+                .conflicts_with("unassign")
+                .help(
+                    "The user or team to assign the issues to.  Users can be given \
+                     by username, email or as 'user:<id>', teams as 'team:<id>'.",
+                ),
+        )
+        .arg(
+            Arg::new("unassign")
+                .long("unassign")
+                .help("Remove the current assignee from all selected issues."),
+        )
+        .arg(dry_run_arg())
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let changes = IssueChanges {
+        assigned_to: Some(matches.value_of("to").unwrap_or_default().into()),
+        ..Default::default()
+    };
+    update_selected_issues(matches, &changes)
+}
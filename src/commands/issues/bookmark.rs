@@ -0,0 +1,26 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::IssueChanges;
+use crate::utils::issues::update_selected_issues;
+
+use super::dry_run_arg;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Bulk bookmark all selected issues.")
+        .arg(
+            Arg::new("remove")
+                .long("remove")
+                .help("Remove the bookmark from the selected issues instead."),
+        )
+        .arg(dry_run_arg())
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let changes = IssueChanges {
+        is_bookmarked: Some(!matches.is_present("remove")),
+        ..Default::default()
+    };
+    update_selected_issues(matches, &changes)
+}
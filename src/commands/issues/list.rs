@@ -0,0 +1,59 @@
+use std::io;
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::{Api, IssueFilter};
+use crate::config::Config;
+use crate::utils::args::validate_int;
+use crate::utils::issues::print_issues;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("List issues in a project.")
+        .arg(
+            Arg::new("max_rows")
+                .long("max-rows")
+                .value_name("MAX_ROWS")
+                .validator(validate_int)
+                .help("Maximum number of issues to list."),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Format outputs as JSON."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let api = Api::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+    let max_rows = match matches.value_of("max_rows") {
+        Some(value) => Some(value.parse()?),
+        None => None,
+    };
+
+    // without an explicit selection we fall back to the server's default
+    // listing instead of selecting nothing.
+    let filter = match IssueFilter::get_filter_from_matches(matches)? {
+        IssueFilter::Empty => IssueFilter::All,
+        filter => filter,
+    };
+    let issues = api.list_issues(&org, &project, &filter, max_rows)?;
+
+    if matches.is_present("json") {
+        serde_json::to_writer_pretty(&mut io::stdout(), &issues)?;
+        println!();
+        return Ok(());
+    }
+
+    if issues.is_empty() {
+        println!("No issues found.");
+        return Ok(());
+    }
+
+    print_issues(&issues);
+
+    Ok(())
+}
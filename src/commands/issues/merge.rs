@@ -0,0 +1,28 @@
+use anyhow::{bail, Result};
+use clap::{ArgMatches, Command};
+
+use crate::api::{IssueChanges, IssueFilter};
+use crate::utils::issues::update_selected_issues;
+
+use super::dry_run_arg;
+
+pub fn make_command(command: Command) -> Command {
+    command.about("Merge all selected issues into one.").arg(dry_run_arg())
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    // Merging cannot be undone, so only explicitly listed issues are merged.
+    match IssueFilter::get_filter_from_matches(matches)? {
+        IssueFilter::ExplicitIds(ref ids) if ids.len() >= 2 => {}
+        IssueFilter::ExplicitIds(_) | IssueFilter::Empty => {
+            bail!("At least two issues are required for merging.")
+        }
+        _ => bail!("Issues can only be merged when selected with --id."),
+    }
+
+    let changes = IssueChanges {
+        merge: Some(true),
+        ..Default::default()
+    };
+    update_selected_issues(matches, &changes)
+}
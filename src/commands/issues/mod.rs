@@ -3,18 +3,33 @@ use clap::{Arg, ArgMatches, Command};
 
 use crate::utils::args::ArgExt;
 
+pub mod assign;
+pub mod bookmark;
+pub mod list;
+pub mod merge;
 pub mod mute;
 pub mod resolve;
 pub mod unresolve;
 
 macro_rules! each_subcommand {
     ($mac:ident) => {
+        $mac!(assign);
+        $mac!(bookmark);
+        $mac!(list);
+        $mac!(merge);
         $mac!(mute);
         $mac!(resolve);
         $mac!(unresolve);
     };
 }
 
+/// The `--dry-run` argument of all subcommands that update issues.
+pub fn dry_run_arg<'a>() -> Arg<'a> {
+    Arg::new("dry_run")
+        .long("dry-run")
+        .help("List the selected issues without updating them.")
+}
+
 pub fn make_command(mut command: Command) -> Command {
     macro_rules! add_subcommand {
         ($name:ident) => {{
@@ -36,14 +51,28 @@ pub fn make_command(mut command: Command) -> Command {
                 .short('s')
                 .value_name("STATUS")
                 .global(true)
+                .conflicts_with_all(&["query", "all", "id"])
                 .possible_values(&["resolved", "muted", "unresolved"])
                 .help("Select all issues matching a given status."),
         )
+        .arg(
+            Arg::new("query")
+                .long("query")
+                .short('q')
+                .value_name("QUERY")
+                .global(true)
+                .conflicts_with_all(&["all", "id"])
+                .help(
+                    "Select all issues matching the given search query, using Sentry's \
+                     search syntax (e.g. 'is:unresolved level:error release:1.0').",
+                ),
+        )
         .arg(
             Arg::new("all")
                 .long("all")
                 .short('a')
                 .global(true)
+                .conflicts_with("id")
                 .help("Select all issues (this might be limited)."),
         )
         .arg(
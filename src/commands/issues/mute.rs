@@ -1,34 +1,66 @@
 use anyhow::Result;
-use clap::{ArgMatches, Command};
-use log::info;
+use clap::{Arg, ArgGroup, ArgMatches, Command};
 
-use crate::api::{Api, IssueChanges, IssueFilter};
-use crate::config::Config;
+use crate::api::{IssueChanges, IssueStatusDetails};
+use crate::utils::args::validate_int;
+use crate::utils::issues::update_selected_issues;
+
+use super::dry_run_arg;
 
 pub fn make_command(command: Command) -> Command {
-    command.about("Bulk mute all selected issues.")
+    command
+        .about("Bulk mute all selected issues.")
+        .arg(
+            Arg::new("until_count")
+                .long("until-count")
+                .value_name("COUNT")
+                .validator(validate_int)
+                .help("Unmute the issues once they occurred this many more times."),
+        )
+        .arg(
+            Arg::new("until_users")
+                .long("until-users")
+                .value_name("COUNT")
+                .validator(validate_int)
+                .help("Unmute the issues once they affected this many more users."),
+        )
+        .group(ArgGroup::new("threshold").args(&["until_count", "until_users"]))
+        .arg(
+            Arg::new("for_window")
+                .long("for-window")
+                .value_name("MINUTES")
+                .validator(validate_int)
+                .requires("threshold")
+                .help(
+                    "Only count occurrences or users within a window of this many \
+                     minutes towards the unmute threshold.",
+                ),
+        )
+        .arg(dry_run_arg())
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
-    let config = Config::current();
-    let (org, project) = config.get_org_and_project(matches)?;
-    let filter = IssueFilter::get_filter_from_matches(matches)?;
-    let mut changes: IssueChanges = Default::default();
-
-    info!(
-        "Issuing a command for Organization: {} Project: {}",
-        org, project
-    );
-
-    changes.new_status = Some("muted".into());
+    let mut changes = IssueChanges {
+        new_status: Some("muted".into()),
+        ..Default::default()
+    };
 
-    if Api::current().bulk_update_issue(&org, &project, &filter, &changes)? {
-        println!("Updated matching issues.");
-        if let Some(status) = changes.new_status.as_ref() {
-            println!("  new status: {}", status);
+    if matches.is_present("threshold") {
+        let window = match matches.value_of("for_window") {
+            Some(value) => Some(value.parse()?),
+            None => None,
+        };
+        let mut details = IssueStatusDetails::default();
+        if let Some(count) = matches.value_of("until_count") {
+            details.ignore_count = Some(count.parse()?);
+            details.ignore_window = window;
         }
-    } else {
-        println!("No changes requested.");
+        if let Some(count) = matches.value_of("until_users") {
+            details.ignore_user_count = Some(count.parse()?);
+            details.ignore_user_window = window;
+        }
+        changes.status_details = Some(details);
     }
-    Ok(())
+
+    update_selected_issues(matches, &changes)
 }
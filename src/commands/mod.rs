@@ -20,6 +20,7 @@ macro_rules! each_subcommand {
         $mac!(bash_hook);
         $mac!(debug_files);
         $mac!(deploys);
+        $mac!(envelopes);
         $mac!(files);
         $mac!(info);
         $mac!(issues);
@@ -32,6 +33,8 @@ macro_rules! each_subcommand {
         $mac!(repos);
         $mac!(send_event);
         $mac!(send_envelope);
+        $mac!(send_session);
+        $mac!(send_transaction);
         $mac!(sourcemaps);
         #[cfg(not(feature = "managed"))]
         $mac!(uninstall);
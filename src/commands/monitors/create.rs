@@ -0,0 +1,50 @@
+use anyhow::{bail, Result};
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::{Api, CreateMonitor};
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+use crate::utils::monitors::{add_monitor_config_args, get_monitor_config_from_matches};
+
+pub fn make_command(command: Command) -> Command {
+    let command = command
+        .about("Create a new monitor.")
+        .org_arg()
+        .arg(
+            Arg::new("name")
+                .value_name("NAME")
+                .required(true)
+                .help("The name of the new monitor."),
+        )
+        .arg(
+            Arg::new("slug")
+                .long("slug")
+                .value_name("SLUG")
+                .help("The slug of the new monitor. [defaults to a slug derived from the name]"),
+        );
+    add_monitor_config_args(command)
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let api = Api::current();
+    let org = config.get_org(matches)?;
+    let monitor_config = get_monitor_config_from_matches(matches)?;
+
+    if monitor_config.schedule.is_none() {
+        bail!("A schedule is required. Pass either --schedule or --interval.");
+    }
+
+    let monitor = api.create_organization_monitor(
+        &org,
+        &CreateMonitor {
+            name: matches.value_of("name").unwrap().to_string(),
+            slug: matches.value_of("slug").map(str::to_string),
+            ty: "cron_job".into(),
+            config: monitor_config,
+        },
+    )?;
+
+    println!("Created monitor {} ({})", monitor.name, monitor.id);
+    Ok(())
+}
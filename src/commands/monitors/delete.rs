@@ -0,0 +1,29 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::Api;
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+
+pub fn make_command(command: Command) -> Command {
+    command.about("Delete a monitor.").org_arg().arg(
+        Arg::new("monitor")
+            .value_name("MONITOR")
+            .required(true)
+            .help("The ID or slug of the monitor."),
+    )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let api = Api::current();
+    let monitor = matches.value_of("monitor").unwrap();
+
+    if api.delete_organization_monitor(&config.get_org(matches)?, monitor)? {
+        println!("Deleted monitor {}!", monitor);
+    } else {
+        println!("Did nothing. Monitor {} does not exist.", monitor);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::Api;
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+use crate::utils::logging::is_quiet_mode;
+use crate::utils::monitors::print_monitor;
+use crate::utils::system::QuietExit;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Print information about a monitor.")
+        .org_arg()
+        .arg(
+            Arg::new("monitor")
+                .value_name("MONITOR")
+                .required(true)
+                .help("The ID or slug of the monitor."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let api = Api::current();
+    let org = config.get_org(matches)?;
+    let monitor = api.get_organization_monitor(&org, matches.value_of("monitor").unwrap())?;
+
+    match monitor {
+        Some(monitor) => {
+            if !is_quiet_mode() {
+                print_monitor(&monitor);
+            }
+            Ok(())
+        }
+        None => Err(QuietExit(1).into()),
+    }
+}
@@ -1,13 +1,23 @@
 use anyhow::Result;
 use clap::{ArgMatches, Command};
 
+pub mod create;
+pub mod delete;
+pub mod info;
 pub mod list;
 pub mod run;
+pub mod sync;
+pub mod update;
 
 macro_rules! each_subcommand {
     ($mac:ident) => {
+        $mac!(create);
+        $mac!(delete);
+        $mac!(info);
         $mac!(list);
         $mac!(run);
+        $mac!(sync);
+        $mac!(update);
     };
 }
 
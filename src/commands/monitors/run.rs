@@ -1,22 +1,49 @@
-use std::process;
-use std::time::Instant;
+use std::borrow::Cow;
+use std::io::{self, Read, Write};
+use std::process::{self, Child, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::{Arg, ArgMatches, Command};
+use log::warn;
+use parking_lot::Mutex;
+use sentry::protocol::{Context, Event, Level, LogEntry, SpanStatus};
+use serde_json::Value;
 use uuid::Uuid;
 
-use crate::api::{Api, CreateMonitorCheckIn, MonitorStatus, UpdateMonitorCheckIn};
-use crate::utils::args::validate_uuid;
-use crate::utils::system::QuietExit;
+use crate::api::{
+    Api, CreateMonitor, CreateMonitorCheckIn, Monitor, MonitorConfig, MonitorStatus, UpdateMonitor,
+    UpdateMonitorCheckIn,
+};
+use crate::config::Config;
+use crate::utils::args::{validate_int, ArgExt};
+use crate::utils::event::{get_sdk_info, with_sentry_client};
+use crate::utils::monitors::{
+    add_monitor_config_args, config_differs, get_monitor_config_from_matches,
+};
+use crate::utils::system::{defer_interrupts, QuietExit};
+use crate::utils::transactions::new_transaction;
+
+/// The number of bytes kept from the end of stdout and stderr each when
+/// capturing the output of the wrapped command.
+const OUTPUT_TAIL_SIZE: usize = 4096;
+
+/// How often the wrapped command is checked for having exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The exit code used when the wrapped command is killed after `--timeout`.
+const TIMEOUT_EXIT_CODE: i32 = 124;
 
 pub fn make_command(command: Command) -> Command {
-    command
+    let command = command
         .about("Wraps a command")
+        .org_arg()
         .arg(
             Arg::new("monitor")
-                .help("The monitor ID")
-                .required(true)
-                .validator(validate_uuid),
+                .help("The monitor ID or slug")
+                .required(true),
         )
         .arg(
             Arg::new("allow_failure")
@@ -24,6 +51,39 @@ pub fn make_command(command: Command) -> Command {
                 .long("allow-failure")
                 .help("Run provided command even when Sentry reports an error."),
         )
+        .arg(
+            Arg::new("capture_output")
+                .long("capture-output")
+                .help(
+                    "Send the exit code and the tail of stdout and stderr of the command \
+                     to Sentry as an event if it fails.",
+                ),
+        )
+        .arg(
+            Arg::new("transaction")
+                .long("transaction")
+                .help("Record the run of the command as a performance transaction in Sentry."),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .value_name("SECONDS")
+                .validator(validate_int)
+                .help(
+                    "Kill the command if it runs longer than the given number of seconds \
+                     and report the check-in as timed out.",
+                ),
+        )
+        .arg(
+            Arg::new("heartbeat")
+                .long("heartbeat")
+                .value_name("SECONDS")
+                .validator(validate_int)
+                .help(
+                    "Refresh the in-progress check-in at the given interval in seconds \
+                     while the command is running.",
+                ),
+        )
         .arg(
             Arg::new("args")
                 .value_name("ARGS")
@@ -31,18 +91,390 @@ pub fn make_command(command: Command) -> Command {
                 .takes_value(true)
                 .multiple_values(true)
                 .last(true),
-        )
+        );
+    add_monitor_config_args(command)
+}
+
+/// Looks up a monitor by slug and creates or updates it if a schedule or
+/// other config values were given on the command line.
+fn upsert_monitor(api: &Api, matches: &ArgMatches, slug: &str) -> Result<Monitor> {
+    let org = Config::current().get_org(matches)?;
+    let wanted = get_monitor_config_from_matches(matches)?;
+
+    match api.get_organization_monitor(&org, slug)? {
+        Some(monitor) if config_differs(&monitor.config, &wanted) => {
+            Ok(api.update_organization_monitor(
+                &org,
+                &monitor.id,
+                &UpdateMonitor {
+                    config: Some(wanted),
+                    ..Default::default()
+                },
+            )?)
+        }
+        Some(monitor) => Ok(monitor),
+        None => {
+            if wanted.schedule.is_none() {
+                bail!(
+                    "Monitor {} does not exist. Pass --schedule or --interval to create it.",
+                    slug
+                );
+            }
+            Ok(api.create_organization_monitor(
+                &org,
+                &CreateMonitor {
+                    name: slug.to_string(),
+                    slug: Some(slug.to_string()),
+                    ty: "cron_job".into(),
+                    config: wanted,
+                },
+            )?)
+        }
+    }
+}
+
+/// Keeps the last `OUTPUT_TAIL_SIZE` bytes written to it.
+#[derive(Default)]
+struct OutputTail(Vec<u8>);
+
+impl OutputTail {
+    fn push(&mut self, data: &[u8]) {
+        self.0.extend_from_slice(data);
+        if self.0.len() > OUTPUT_TAIL_SIZE {
+            let excess = self.0.len() - OUTPUT_TAIL_SIZE;
+            self.0.drain(..excess);
+        }
+    }
+
+    fn to_string_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.0).into_owned()
+    }
+}
+
+/// Copies everything from `src` to `dst` while keeping the tail of it.
+fn tee<R: Read, W: Write>(mut src: R, mut dst: W, tail: Arc<Mutex<OutputTail>>) {
+    let mut buf = [0; 8192];
+    loop {
+        match src.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                dst.write_all(&buf[..n]).ok();
+                dst.flush().ok();
+                tail.lock().push(&buf[..n]);
+            }
+        }
+    }
+}
+
+/// The tail of stdout and stderr of a running command.
+struct CapturedOutput {
+    stdout: Arc<Mutex<OutputTail>>,
+    stderr: Arc<Mutex<OutputTail>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl CapturedOutput {
+    /// Starts passing through the piped output of `child`.
+    fn attach(child: &mut Child) -> CapturedOutput {
+        let stdout = Arc::new(Mutex::new(OutputTail::default()));
+        let stderr = Arc::new(Mutex::new(OutputTail::default()));
+        let mut threads = vec![];
+        if let Some(pipe) = child.stdout.take() {
+            let tail = stdout.clone();
+            threads.push(thread::spawn(move || tee(pipe, io::stdout(), tail)));
+        }
+        if let Some(pipe) = child.stderr.take() {
+            let tail = stderr.clone();
+            threads.push(thread::spawn(move || tee(pipe, io::stderr(), tail)));
+        }
+        CapturedOutput {
+            stdout,
+            stderr,
+            threads,
+        }
+    }
+
+    /// Returns the tails of stdout and stderr.
+    ///
+    /// With `drain` the output is read until the pipes are closed.  This is
+    /// skipped for killed commands, whose pipes might be kept open by
+    /// processes they spawned.
+    fn finish(self, drain: bool) -> (String, String) {
+        if drain {
+            for thread in self.threads {
+                thread.join().ok();
+            }
+        }
+        let stdout = self.stdout.lock().to_string_lossy();
+        let stderr = self.stderr.lock().to_string_lossy();
+        (stdout, stderr)
+    }
+}
+
+/// Records SIGINT and SIGTERM received while the command runs so they can be
+/// forwarded to it.
+#[cfg(not(windows))]
+struct SignalForwarder(Arc<std::sync::atomic::AtomicUsize>);
+
+#[cfg(not(windows))]
+impl SignalForwarder {
+    fn new() -> Result<SignalForwarder> {
+        use signal_hook::consts::{SIGINT, SIGTERM};
+
+        let received = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        for &signal in &[SIGINT, SIGTERM] {
+            signal_hook::flag::register_usize(signal, received.clone(), signal as usize)?;
+        }
+        defer_interrupts();
+        Ok(SignalForwarder(received))
+    }
+
+    /// Forwards a pending signal to `child`.  Returns `true` if there was one.
+    fn forward(&self, child: &Child) -> bool {
+        match self.0.swap(0, std::sync::atomic::Ordering::SeqCst) {
+            0 => false,
+            signal => {
+                unsafe {
+                    libc::kill(child.id() as libc::pid_t, signal as libc::c_int);
+                }
+                true
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+struct SignalForwarder;
+
+#[cfg(windows)]
+impl SignalForwarder {
+    fn new() -> Result<SignalForwarder> {
+        defer_interrupts();
+        Ok(SignalForwarder)
+    }
+
+    fn forward(&self, _child: &Child) -> bool {
+        false
+    }
+}
+
+/// How a run of the wrapped command ended.
+enum RunOutcome {
+    /// The command exited on its own.
+    Exited(ExitStatus),
+    /// The command exited after a signal was forwarded to it.
+    Interrupted(ExitStatus),
+    /// The command was killed after running longer than `--timeout`.
+    TimedOut,
+}
+
+impl RunOutcome {
+    fn success(&self) -> bool {
+        matches!(self, RunOutcome::Exited(status) if status.success())
+    }
+
+    fn checkin_status(&self) -> MonitorStatus {
+        match self {
+            RunOutcome::TimedOut => MonitorStatus::Timeout,
+            _ if self.success() => MonitorStatus::Ok,
+            _ => MonitorStatus::Error,
+        }
+    }
+
+    fn span_status(&self) -> SpanStatus {
+        match self {
+            RunOutcome::TimedOut => SpanStatus::DeadlineExceeded,
+            RunOutcome::Interrupted(_) => SpanStatus::Cancelled,
+            _ if self.success() => SpanStatus::Ok,
+            _ => SpanStatus::InternalError,
+        }
+    }
+
+    fn exit_code(&self) -> i32 {
+        match self {
+            RunOutcome::Exited(status) | RunOutcome::Interrupted(status) => {
+                status.code().unwrap_or(1)
+            }
+            RunOutcome::TimedOut => TIMEOUT_EXIT_CODE,
+        }
+    }
+}
+
+/// Waits for `child` to exit, killing it after `timeout` and calling
+/// `send_heartbeat` every `heartbeat` while it runs.
+fn wait_for_child<F: FnMut()>(
+    child: &mut Child,
+    timeout: Option<Duration>,
+    heartbeat: Option<Duration>,
+    mut send_heartbeat: F,
+) -> Result<RunOutcome> {
+    let signals = SignalForwarder::new()?;
+    let started = Instant::now();
+    let mut last_heartbeat = started;
+    let mut interrupted = false;
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(if interrupted {
+                RunOutcome::Interrupted(status)
+            } else {
+                RunOutcome::Exited(status)
+            });
+        }
+
+        if signals.forward(child) {
+            interrupted = true;
+        }
+
+        if let Some(timeout) = timeout {
+            if started.elapsed() >= timeout {
+                child.kill().ok();
+                child.wait()?;
+                return Ok(RunOutcome::TimedOut);
+            }
+        }
+
+        if let Some(heartbeat) = heartbeat {
+            if last_heartbeat.elapsed() >= heartbeat {
+                send_heartbeat();
+                last_heartbeat = Instant::now();
+            }
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Sends an event describing a failed run of a monitor.
+fn send_failure_event(
+    monitor: &str,
+    checkin_id: Option<&Uuid>,
+    args: &[&str],
+    outcome: &RunOutcome,
+    stdout: String,
+    stderr: String,
+) -> Result<Uuid> {
+    let dsn = Config::current().get_dsn()?;
+    let exit_code = match outcome {
+        RunOutcome::Exited(status) | RunOutcome::Interrupted(status) => status.code(),
+        RunOutcome::TimedOut => None,
+    };
+
+    let (message, reason) = match (outcome, exit_code) {
+        (RunOutcome::TimedOut, _) => (format!("Monitor {} timed out", monitor), "timeout".into()),
+        (RunOutcome::Interrupted(_), _) => (
+            format!("Monitor {} was interrupted", monitor),
+            "interrupted".into(),
+        ),
+        (_, Some(code)) => (
+            format!("Monitor {} failed with exit code {}", monitor, code),
+            code.to_string(),
+        ),
+        (_, None) => (
+            format!("Monitor {} was terminated by a signal", monitor),
+            "signal".into(),
+        ),
+    };
+
+    let mut event = Event {
+        sdk: Some(get_sdk_info()),
+        level: Level::Error,
+        platform: "other".into(),
+        logentry: Some(LogEntry {
+            message,
+            params: vec![],
+        }),
+        fingerprint: Cow::Owned(vec![
+            "monitor-run".into(),
+            monitor.to_string().into(),
+            reason.into(),
+        ]),
+        ..Event::default()
+    };
+
+    event.tags.insert("monitor".into(), monitor.into());
+    if let Some(code) = exit_code {
+        event.tags.insert("exit_code".into(), code.to_string());
+    }
+
+    let mut context = vec![
+        ("id".to_string(), Value::from(monitor)),
+        ("command".to_string(), Value::from(args.join(" "))),
+    ];
+    if let Some(checkin_id) = checkin_id {
+        context.push(("checkin_id".to_string(), Value::from(checkin_id.to_string())));
+    }
+    event.contexts.insert(
+        "monitor".into(),
+        Context::Other(context.into_iter().collect()),
+    );
+
+    event.extra.insert("stdout".into(), Value::from(stdout));
+    event.extra.insert("stderr".into(), Value::from(stderr));
+
+    Ok(with_sentry_client(dsn, |c| c.capture_event(event, None)))
+}
+
+/// Sends a transaction covering the run of a monitor.
+fn send_run_transaction(
+    monitor: &str,
+    args: &[&str],
+    started: SystemTime,
+    outcome: &RunOutcome,
+) -> Result<Uuid> {
+    let config = Config::current();
+    let dsn = config.get_dsn()?;
+
+    let mut transaction = new_transaction(
+        monitor,
+        "monitor.run",
+        started,
+        SystemTime::now(),
+        outcome.span_status(),
+    );
+    transaction.environment = config.get_environment().map(Into::into);
+    transaction.tags.insert("monitor".into(), monitor.into());
+    if let RunOutcome::Exited(status) | RunOutcome::Interrupted(status) = outcome {
+        if let Some(code) = status.code() {
+            transaction
+                .tags
+                .insert("exit_code".into(), code.to_string());
+        }
+    }
+    transaction
+        .extra
+        .insert("command".into(), Value::from(args.join(" ")));
+
+    let id = transaction.event_id;
+    with_sentry_client(dsn, |c| c.send_envelope(transaction.into()));
+    Ok(id)
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let api = Api::current();
 
-    let monitor = matches
-        .value_of("monitor")
-        .map(|x| x.parse::<Uuid>().unwrap())
-        .unwrap();
+    let monitor_arg = matches.value_of("monitor").unwrap();
+    let monitor = match monitor_arg.parse::<Uuid>() {
+        Ok(_) if get_monitor_config_from_matches(matches)? != MonitorConfig::default() => {
+            bail!(
+                "Monitor config arguments cannot be used with a monitor ID. \
+                 Pass the monitor slug or use `sentry-cli monitors update` instead."
+            );
+        }
+        Ok(uuid) => uuid,
+        Err(_) => upsert_monitor(&api, matches, monitor_arg)?.id.parse()?,
+    };
 
     let allow_failure = matches.is_present("allow_failure");
+    let capture_output = matches.is_present("capture_output");
+    let timeout = match matches.value_of("timeout") {
+        Some(value) => Some(Duration::from_secs(value.parse()?)),
+        None => None,
+    };
+    let heartbeat = match matches.value_of("heartbeat") {
+        Some(value) => Some(Duration::from_secs(value.parse()?)),
+        None => None,
+    };
     let args: Vec<_> = matches.values_of("args").unwrap().collect();
 
     let monitor_checkin = api.create_monitor_checkin(
@@ -51,11 +483,36 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             status: MonitorStatus::InProgress,
         },
     );
+    let checkin_id = monitor_checkin.as_ref().ok().map(|checkin| checkin.id);
 
     let started = Instant::now();
+    let started_at = SystemTime::now();
     let mut p = process::Command::new(args[0]);
     p.args(&args[1..]);
-    let exit_status = p.status()?;
+    if capture_output {
+        p.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+    let mut child = p.spawn()?;
+    let output = if capture_output {
+        Some(CapturedOutput::attach(&mut child))
+    } else {
+        None
+    };
+
+    let outcome = wait_for_child(&mut child, timeout, heartbeat, || {
+        if let Some(ref checkin_id) = checkin_id {
+            if let Err(err) = api.update_monitor_checkin(
+                &monitor,
+                checkin_id,
+                &UpdateMonitorCheckIn {
+                    status: Some(MonitorStatus::InProgress),
+                    ..Default::default()
+                },
+            ) {
+                warn!("Could not send heartbeat: {}", err);
+            }
+        }
+    })?;
 
     match monitor_checkin {
         Ok(checkin) => {
@@ -63,11 +520,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                 &monitor,
                 &checkin.id,
                 &UpdateMonitorCheckIn {
-                    status: Some(if exit_status.success() {
-                        MonitorStatus::Ok
-                    } else {
-                        MonitorStatus::Error
-                    }),
+                    status: Some(outcome.checkin_status()),
                     duration: Some({
                         let elapsed = started.elapsed();
                         elapsed.as_secs() * 1000 + u64::from(elapsed.subsec_millis())
@@ -85,13 +538,36 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
     }
 
-    if !exit_status.success() {
-        if let Some(code) = exit_status.code() {
-            Err(QuietExit(code).into())
-        } else {
-            Err(QuietExit(1).into())
+    if matches.is_present("transaction") {
+        if let Err(err) = send_run_transaction(monitor_arg, &args, started_at, &outcome) {
+            warn!("Could not send transaction: {}", err);
         }
-    } else {
+    }
+
+    if let RunOutcome::TimedOut = outcome {
+        eprintln!(
+            "Command timed out after {} seconds.",
+            timeout.map_or(0, |t| t.as_secs())
+        );
+    }
+
+    let output = output.map(|output| output.finish(matches!(outcome, RunOutcome::Exited(_))));
+    if let (false, Some((stdout, stderr))) = (outcome.success(), output) {
+        if let Err(err) = send_failure_event(
+            monitor_arg,
+            checkin_id.as_ref(),
+            &args,
+            &outcome,
+            stdout,
+            stderr,
+        ) {
+            warn!("Could not send failure event: {}", err);
+        }
+    }
+
+    if outcome.success() {
         Ok(())
+    } else {
+        Err(QuietExit(outcome.exit_code()).into())
     }
 }
@@ -0,0 +1,173 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+use serde::Deserialize;
+
+use crate::api::{
+    Api, CreateMonitor, Monitor, MonitorConfig, MonitorSchedule, UpdateMonitor,
+};
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+use crate::utils::monitors::{parse_interval, with_config_defaults};
+
+/// A single monitor as declared in a monitors file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MonitorDefinition {
+    slug: String,
+    name: Option<String>,
+    schedule: Option<String>,
+    interval: Option<String>,
+    checkin_margin: Option<u64>,
+    max_runtime: Option<u64>,
+    timezone: Option<String>,
+}
+
+impl MonitorDefinition {
+    fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.slug)
+    }
+
+    fn config(&self) -> Result<MonitorConfig> {
+        let schedule = match (&self.schedule, &self.interval) {
+            (Some(crontab), None) => MonitorSchedule::Crontab(crontab.clone()),
+            (None, Some(interval)) => parse_interval(interval)?,
+            (Some(_), Some(_)) => bail!("only one of schedule and interval may be given"),
+            (None, None) => bail!("either schedule or interval is required"),
+        };
+        Ok(MonitorConfig {
+            schedule_type: Some(schedule.schedule_type()),
+            schedule: Some(schedule),
+            checkin_margin: self.checkin_margin,
+            max_runtime: self.max_runtime,
+            timezone: self.timezone.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MonitorsFile {
+    #[serde(default)]
+    monitors: Vec<MonitorDefinition>,
+}
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Reconcile the monitors declared in a file with the server.")
+        .org_arg()
+        .arg(
+            Arg::new("file")
+                .long("file")
+                .short('f')
+                .value_name("PATH")
+                .required(true)
+                .help(
+                    "The path to a YAML file declaring the monitors. Config values that \
+                     are not declared are reset to their defaults.",
+                ),
+        )
+        .arg(
+            Arg::new("delete")
+                .long("delete")
+                .help("Delete monitors on the server that are not declared in the file."),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .help("Print the changes that would be made without applying them."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let api = Api::current();
+    let org = config.get_org(matches)?;
+    let dry_run = matches.is_present("dry_run");
+
+    let path = Path::new(matches.value_of("file").unwrap());
+    let file: MonitorsFile = serde_yaml::from_reader(
+        File::open(path).with_context(|| format!("Could not open {}", path.display()))?,
+    )
+    .with_context(|| format!("Could not parse {}", path.display()))?;
+
+    let mut declared = HashSet::new();
+    for definition in &file.monitors {
+        if !declared.insert(definition.slug.as_str()) {
+            bail!("Monitor {} is declared more than once.", definition.slug);
+        }
+    }
+
+    let existing: HashMap<String, Monitor> = api
+        .list_organization_monitors(&org)?
+        .into_iter()
+        .map(|monitor| (monitor.slug().to_string(), monitor))
+        .collect();
+
+    for definition in &file.monitors {
+        // Values that are not declared are reset to their defaults, so the
+        // full config is compared and sent.
+        let wanted = with_config_defaults(
+            definition
+                .config()
+                .with_context(|| format!("Invalid definition for monitor {}", definition.slug))?,
+        );
+
+        match existing.get(&definition.slug) {
+            None => {
+                println!("{} {}", style("+ create").green(), definition.slug);
+                if !dry_run {
+                    api.create_organization_monitor(
+                        &org,
+                        &CreateMonitor {
+                            name: definition.name().to_string(),
+                            slug: Some(definition.slug.clone()),
+                            ty: "cron_job".into(),
+                            config: wanted,
+                        },
+                    )?;
+                }
+            }
+            Some(monitor)
+                if monitor.name != definition.name()
+                    || with_config_defaults(monitor.config.clone()) != wanted =>
+            {
+                println!("{} {}", style("~ update").yellow(), definition.slug);
+                if !dry_run {
+                    api.update_organization_monitor(
+                        &org,
+                        &monitor.id,
+                        &UpdateMonitor {
+                            name: Some(definition.name().to_string()),
+                            config: Some(wanted),
+                            ..Default::default()
+                        },
+                    )?;
+                }
+            }
+            Some(_) => {
+                println!("{} {}", style("  unchanged").dim(), definition.slug);
+            }
+        }
+    }
+
+    if matches.is_present("delete") {
+        let mut stale: Vec<_> = existing
+            .iter()
+            .filter(|(slug, _)| !declared.contains(slug.as_str()))
+            .collect();
+        stale.sort_by_key(|(slug, _)| slug.as_str());
+        for (slug, monitor) in stale {
+            println!("{} {}", style("- delete").red(), slug);
+            if !dry_run {
+                api.delete_organization_monitor(&org, &monitor.id)?;
+            }
+        }
+    }
+
+    Ok(())
+}
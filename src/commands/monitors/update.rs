@@ -0,0 +1,56 @@
+use anyhow::{bail, Result};
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::{Api, MonitorConfig, UpdateMonitor};
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+use crate::utils::monitors::{add_monitor_config_args, get_monitor_config_from_matches};
+
+pub fn make_command(command: Command) -> Command {
+    let command = command
+        .about("Update an existing monitor.")
+        .org_arg()
+        .arg(
+            Arg::new("monitor")
+                .value_name("MONITOR")
+                .required(true)
+                .help("The ID or slug of the monitor."),
+        )
+        .arg(
+            Arg::new("name")
+                .long("name")
+                .value_name("NAME")
+                .help("Rename the monitor."),
+        );
+    add_monitor_config_args(command)
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let api = Api::current();
+    let org = config.get_org(matches)?;
+    let monitor_config = get_monitor_config_from_matches(matches)?;
+
+    let changes = UpdateMonitor {
+        name: matches.value_of("name").map(str::to_string),
+        config: if monitor_config == MonitorConfig::default() {
+            None
+        } else {
+            Some(monitor_config)
+        },
+        ..Default::default()
+    };
+
+    if changes.name.is_none() && changes.config.is_none() {
+        bail!("No changes requested.");
+    }
+
+    let monitor = api.update_organization_monitor(
+        &org,
+        matches.value_of("monitor").unwrap(),
+        &changes,
+    )?;
+
+    println!("Updated monitor {} ({})", monitor.name, monitor.id);
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use clap::{Arg, ArgMatches, Command};
@@ -32,6 +32,15 @@ pub fn make_command(command: Command) -> Command {
                 .required(true)
                 .help("The path to a bundle that should be uploaded."),
         )
+        .arg(
+            Arg::new("hermes_sourcemap")
+                .long("hermes-sourcemap")
+                .value_name("PATH")
+                .help(
+                    "The path to the sourcemap of the hermes compiler. It is composed \
+                    with the packager sourcemap given by --sourcemap.",
+                ),
+        )
         .arg(
             Arg::new("release")
                 .long("release")
@@ -94,6 +103,10 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         debug!("Non-file bundle found");
     }
 
+    if let Some(hermes_sourcemap) = matches.value_of("hermes_sourcemap") {
+        processor.compose_hermes_sourcemap(&sourcemap_url, Path::new(hermes_sourcemap))?;
+    }
+
     processor.rewrite(&[base.to_str().unwrap()])?;
     processor.add_sourcemap_references()?;
 
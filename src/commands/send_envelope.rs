@@ -1,6 +1,7 @@
+use std::fs;
 use std::path::PathBuf;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::{Arg, ArgMatches, Command};
 use glob::{glob_with, MatchOptions};
 use log::{debug, warn};
@@ -8,7 +9,9 @@ use sentry::types::Dsn;
 use sentry::Envelope;
 
 use crate::config::Config;
+use crate::utils::args::ArgExt;
 use crate::utils::event::with_sentry_client;
+use crate::utils::spool::{flush_spool, get_spool_dir, send_or_spool, Dispatched};
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -18,14 +21,24 @@ pub fn make_command(command: Command) -> Command {
              This command will validate and attempt to send an envelope to Sentry. \
              Due to network errors, rate limits or sampling the envelope is not guaranteed to \
              actually arrive. Check debug output for transmission errors by passing --log-level=\
-             debug or setting `SENTRY_LOG_LEVEL=debug`.",
+             debug or setting `SENTRY_LOG_LEVEL=debug`.{n}{n}\
+             If a spool directory is configured, envelopes that could not be sent are written \
+             to it and can be sent again later with --flush-spool.",
         )
         .arg(
             Arg::new("path")
                 .value_name("PATH")
-                .required(true)
+                .required_unless_present("flush_spool")
                 .help("The path or glob to the file(s) in envelope format to send as envelope(s)."),
         )
+        .arg(
+            Arg::new("flush_spool")
+                .long("flush-spool")
+                .visible_alias("retry-spool")
+                .conflicts_with("path")
+                .help("Send all envelopes in the spool directory again."),
+        )
+        .spool_dir_arg()
 }
 
 fn send_raw_envelope(envelope: Envelope, dsn: Dsn) {
@@ -36,6 +49,14 @@ fn send_raw_envelope(envelope: Envelope, dsn: Dsn) {
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let dsn = config.get_dsn()?;
+    let spool_dir = get_spool_dir(matches);
+
+    if matches.is_present("flush_spool") {
+        return match spool_dir {
+            Some(dir) => flush_spool(&dsn, &dir),
+            None => bail!("No spool directory configured. Pass --spool-dir or set SENTRY_SPOOL_DIR."),
+        };
+    }
 
     let path = matches.value_of("path").unwrap();
 
@@ -51,9 +72,23 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
     for path in collected_paths {
         let p = path.as_path();
-        let envelope: Envelope = Envelope::from_path(p)?;
-        send_raw_envelope(envelope, dsn.clone());
-        println!("Envelope from file {} dispatched", p.display());
+        if let Some(ref spool_dir) = spool_dir {
+            let bytes = fs::read(p)?;
+            let envelope = Envelope::from_slice(&bytes)?;
+            debug!("{:?}", envelope);
+            match send_or_spool(&dsn, &bytes, spool_dir)? {
+                Dispatched::Sent => println!("Envelope from file {} sent", p.display()),
+                Dispatched::Spooled(spooled) => println!(
+                    "Envelope from file {} could not be sent and was spooled to {}",
+                    p.display(),
+                    spooled.display()
+                ),
+            }
+        } else {
+            let envelope: Envelope = Envelope::from_path(p)?;
+            send_raw_envelope(envelope, dsn.clone());
+            println!("Envelope from file {} dispatched", p.display());
+        }
     }
 
     Ok(())
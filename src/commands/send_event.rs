@@ -2,9 +2,9 @@ use std::borrow::Cow;
 use std::env;
 use std::fs::File;
 use std::io::BufReader;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use anyhow::{format_err, Result};
+use anyhow::{bail, format_err, Result};
 use clap::{Arg, ArgMatches, Command};
 use glob::{glob_with, MatchOptions};
 use itertools::Itertools;
@@ -15,9 +15,10 @@ use serde_json::Value;
 use username::get_user_name;
 
 use crate::config::Config;
-use crate::utils::args::{get_timestamp, validate_timestamp};
+use crate::utils::args::{get_timestamp, validate_timestamp, ArgExt};
 use crate::utils::event::{attach_logfile, get_sdk_info, with_sentry_client};
 use crate::utils::releases::detect_release_name;
+use crate::utils::spool::{capture_or_spool, get_spool_dir, Dispatched};
 
 pub fn make_command(command: Command) -> Command {
     command.about("Send a manual event to Sentry.")
@@ -146,6 +147,7 @@ pub fn make_command(command: Command) -> Command {
                     eg. \"INFO: Something broke\" will be parsed as a breadcrumb \
                     \"{\"level\": \"info\", \"message\": \"Something broke\"}\"")
         )
+        .spool_dir_arg()
 }
 
 fn send_raw_event(event: Event<'static>, dsn: Dsn) -> Uuid {
@@ -153,9 +155,25 @@ fn send_raw_event(event: Event<'static>, dsn: Dsn) -> Uuid {
     with_sentry_client(dsn, |c| c.capture_event(event, None))
 }
 
+/// Sends the event, spooling it on failure, and prints the outcome.
+fn send_or_spool_event(event: Event<'static>, dsn: Dsn, spool_dir: &Path, source: &str) -> Result<()> {
+    debug!("{:?}", event);
+    match capture_or_spool(event, dsn, spool_dir)? {
+        (id, Dispatched::Sent) => println!("{} sent: {}", source, id),
+        (id, Dispatched::Spooled(path)) => println!(
+            "{} could not be sent and was spooled to {}: {}",
+            source,
+            path.display(),
+            id
+        ),
+    }
+    Ok(())
+}
+
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let dsn = config.get_dsn()?;
+    let spool_dir = get_spool_dir(matches);
 
     if let Some(path) = matches.value_of("path") {
         let collected_paths: Vec<PathBuf> = glob_with(path, MatchOptions::new())
@@ -168,15 +186,32 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             return Ok(());
         }
 
-        for path in collected_paths {
+        let mut failed = 0;
+        for path in &collected_paths {
             let p = path.as_path();
             let file = File::open(p)?;
             let reader = BufReader::new(file);
             let event: Event = serde_json::from_reader(reader)?;
-            let id = send_raw_event(event, dsn.clone());
-            println!("Event from file {} dispatched: {}", p.display(), id);
+            if let Some(ref spool_dir) = spool_dir {
+                // Keep sending the remaining files if Sentry rejects one.
+                let source = format!("Event from file {}", p.display());
+                if let Err(err) = send_or_spool_event(event, dsn.clone(), spool_dir, &source) {
+                    println!("{} could not be sent: {:#}", source, err);
+                    failed += 1;
+                }
+            } else {
+                let id = send_raw_event(event, dsn.clone());
+                println!("Event from file {} dispatched: {}", p.display(), id);
+            }
         }
 
+        if failed > 0 {
+            bail!(
+                "{} of {} events could not be sent",
+                failed,
+                collected_paths.len()
+            );
+        }
         return Ok(());
     }
 
@@ -284,8 +319,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         attach_logfile(&mut event, logfile, matches.is_present("with_categories"))?;
     }
 
-    let id = send_raw_event(event, dsn);
-    println!("Event dispatched: {}", id);
+    if let Some(ref spool_dir) = spool_dir {
+        send_or_spool_event(event, dsn, spool_dir, "Event")?;
+    } else {
+        let id = send_raw_event(event, dsn);
+        println!("Event dispatched: {}", id);
+    }
 
     Ok(())
 }
@@ -0,0 +1,235 @@
+use std::borrow::Cow;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgGroup, ArgMatches, Command};
+use log::debug;
+use sentry::protocol::{
+    SessionAggregateItem, SessionAggregates, SessionAttributes, SessionStatus, SessionUpdate,
+};
+use sentry::types::Uuid;
+use sentry::Envelope;
+
+use crate::config::Config;
+use crate::utils::args::{get_timestamp, validate_int, validate_timestamp, ArgExt};
+use crate::utils::event::{serialize_envelope, with_sentry_client};
+use crate::utils::releases::detect_release_name;
+use crate::utils::spool::{get_spool_dir, send_or_spool, Dispatched};
+
+/// The arguments counting sessions in aggregate mode.
+const AGGREGATE_ARGS: &[&str] = &["exited", "errored", "crashed", "abnormal"];
+
+/// The longest session duration accepted, one year in seconds.
+const MAX_DURATION: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Send release health session data to Sentry.")
+        .long_about(
+            "Send release health session data to Sentry.{n}{n}\
+             By default a single session is sent. Pass any of --exited, --errored, --crashed \
+             or --abnormal to send the number of sessions per outcome as an aggregate instead.{n}{n}\
+             Due to network errors or rate limits the session is not guaranteed to actually \
+             arrive. Check debug output for transmission errors by passing --log-level=debug \
+             or setting `SENTRY_LOG_LEVEL=debug`.",
+        )
+        .arg(
+            Arg::new("release")
+                .value_name("RELEASE")
+                .long("release")
+                .short('r')
+                .help("The release of the session. Detected automatically if not given."),
+        )
+        .arg(
+            Arg::new("environment")
+                .value_name("ENVIRONMENT")
+                .long("env")
+                .short('E')
+                .help("The environment of the session."),
+        )
+        .arg(
+            Arg::new("started")
+                .value_name("TIMESTAMP")
+                .long("started")
+                .validator(validate_timestamp)
+                .help(
+                    "When the session or aggregate bucket started, as unix timestamp, RFC2822 \
+                     or RFC3339. Defaults to now minus the duration.",
+                ),
+        )
+        .arg(
+            Arg::new("distinct_id")
+                .value_name("ID")
+                .long("distinct-id")
+                .help("An identifier of the user or installation the session belongs to."),
+        )
+        .arg(
+            Arg::new("status")
+                .value_name("STATUS")
+                .long("status")
+                .possible_values(["ok", "exited", "crashed", "abnormal"])
+                .default_value("exited")
+                .help("The status of the session."),
+        )
+        .arg(
+            Arg::new("duration")
+                .value_name("SECONDS")
+                .long("duration")
+                .validator(validate_duration)
+                .help("The duration of the session in seconds."),
+        )
+        .arg(
+            Arg::new("errors")
+                .value_name("COUNT")
+                .long("errors")
+                .validator(validate_int)
+                .help("The number of errors that occurred during the session."),
+        )
+        .group(
+            ArgGroup::new("single")
+                .args(&["status", "duration", "errors"])
+                .multiple(true)
+                .conflicts_with("aggregate"),
+        )
+        .arg(
+            Arg::new("exited")
+                .value_name("COUNT")
+                .long("exited")
+                .validator(validate_int)
+                .help("Send an aggregate with the given number of sessions that exited normally."),
+        )
+        .arg(
+            Arg::new("errored")
+                .value_name("COUNT")
+                .long("errored")
+                .validator(validate_int)
+                .help("Send an aggregate with the given number of sessions with errors."),
+        )
+        .arg(
+            Arg::new("crashed")
+                .value_name("COUNT")
+                .long("crashed")
+                .validator(validate_int)
+                .help("Send an aggregate with the given number of crashed sessions."),
+        )
+        .arg(
+            Arg::new("abnormal")
+                .value_name("COUNT")
+                .long("abnormal")
+                .validator(validate_int)
+                .help("Send an aggregate with the given number of abnormally ended sessions."),
+        )
+        .group(
+            ArgGroup::new("aggregate")
+                .args(AGGREGATE_ARGS)
+                .multiple(true),
+        )
+        .spool_dir_arg()
+}
+
+fn get_duration(value: &str) -> Result<Duration> {
+    match value.parse::<f64>() {
+        Ok(duration) if duration.is_finite() && (0.0..=MAX_DURATION).contains(&duration) => {
+            Ok(Duration::try_from_secs_f64(duration)?)
+        }
+        _ => bail!("Invalid duration, a positive number of seconds up to one year is required."),
+    }
+}
+
+fn validate_duration(value: &str) -> Result<(), String> {
+    get_duration(value).map(|_| ()).map_err(|err| err.to_string())
+}
+
+fn get_count(matches: &ArgMatches, name: &str) -> Result<u32> {
+    match matches.value_of(name) {
+        Some(value) => value
+            .parse()
+            .with_context(|| format!("Invalid value for --{}", name)),
+        None => Ok(0),
+    }
+}
+
+fn get_attributes(matches: &ArgMatches) -> Result<SessionAttributes<'static>> {
+    let release = match matches.value_of("release") {
+        Some(release) => release.to_string(),
+        None => detect_release_name()
+            .context("Could not detect a release. Pass it with --release.")?,
+    };
+    Ok(SessionAttributes {
+        release: Cow::Owned(release),
+        environment: matches
+            .value_of("environment")
+            .map(str::to_string)
+            .or_else(|| Config::current().get_environment())
+            .map(Cow::Owned),
+        ip_address: None,
+        user_agent: None,
+    })
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let dsn = config.get_dsn()?;
+    let spool_dir = get_spool_dir(matches);
+    let attributes = get_attributes(matches)?;
+
+    let duration = matches.value_of("duration").map(get_duration).transpose()?;
+    let started = match matches.value_of("started") {
+        Some(value) => get_timestamp(value)?.into(),
+        None => SystemTime::now()
+            .checked_sub(duration.unwrap_or_default())
+            .context("Invalid duration, the session would start before the epoch.")?,
+    };
+    let distinct_id = matches.value_of("distinct_id").map(str::to_string);
+
+    let mut envelope = Envelope::new();
+    let description = if matches.is_present("aggregate") {
+        envelope.add_item(SessionAggregates {
+            aggregates: vec![SessionAggregateItem {
+                started,
+                distinct_id,
+                exited: get_count(matches, "exited")?,
+                errored: get_count(matches, "errored")?,
+                abnormal: get_count(matches, "abnormal")?,
+                crashed: get_count(matches, "crashed")?,
+            }],
+            attributes,
+        });
+        "Session aggregate".to_string()
+    } else {
+        let session_id = Uuid::new_v4();
+        envelope.add_item(SessionUpdate {
+            session_id,
+            distinct_id,
+            sequence: None,
+            timestamp: None,
+            started,
+            init: true,
+            duration: duration.map(|duration| duration.as_secs_f64()),
+            status: matches.value_of("status").unwrap().parse::<SessionStatus>()?,
+            errors: match matches.value_of("errors") {
+                Some(value) => value.parse()?,
+                None => 0,
+            },
+            attributes,
+        });
+        format!("Session {}", session_id)
+    };
+    debug!("{:?}", envelope);
+
+    if let Some(ref spool_dir) = spool_dir {
+        match send_or_spool(&dsn, &serialize_envelope(&envelope)?, spool_dir)? {
+            Dispatched::Sent => println!("{} sent", description),
+            Dispatched::Spooled(path) => println!(
+                "{} could not be sent and was spooled to {}",
+                description,
+                path.display()
+            ),
+        }
+    } else {
+        with_sentry_client(dsn, |c| c.send_envelope(envelope));
+        println!("{} dispatched", description);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use anyhow::{Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use log::debug;
+use sentry::Envelope;
+
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+use crate::utils::event::{serialize_envelope, with_sentry_client};
+use crate::utils::releases::detect_release_name;
+use crate::utils::spool::{get_spool_dir, send_or_spool, Dispatched};
+use crate::utils::transactions::TransactionDescription;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Send a transaction with child spans to Sentry.")
+        .long_about(
+            "Send a transaction with child spans to Sentry.{n}{n}\
+             The transaction is read from a JSON file with a `name`, an optional `op`, \
+             `status` and `tags`, its timing as `start_timestamp` and `timestamp` or \
+             `duration`, and a list of `spans`. Spans are timed with `start_timestamp` or an \
+             `offset` in seconds from the start of their parent, and `timestamp` or \
+             `duration`. Spans can contain `spans` of their own.{n}{n}\
+             Due to network errors, rate limits or sampling the transaction is not guaranteed \
+             to actually arrive. Check debug output for transmission errors by passing \
+             --log-level=debug or setting `SENTRY_LOG_LEVEL=debug`.",
+        )
+        .arg(
+            Arg::new("path")
+                .value_name("PATH")
+                .required(true)
+                .help("The path to the JSON file describing the transaction."),
+        )
+        .arg(
+            Arg::new("release")
+                .value_name("RELEASE")
+                .long("release")
+                .short('r')
+                .help("Optional identifier of the release."),
+        )
+        .arg(
+            Arg::new("environment")
+                .value_name("ENVIRONMENT")
+                .long("env")
+                .short('E')
+                .help("Send with a specific environment."),
+        )
+        .spool_dir_arg()
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let dsn = config.get_dsn()?;
+    let spool_dir = get_spool_dir(matches);
+
+    let path = matches.value_of("path").unwrap();
+    let file = File::open(path).with_context(|| format!("Could not open {}", path))?;
+    let description: TransactionDescription = serde_json::from_reader(BufReader::new(file))
+        .with_context(|| format!("Could not parse {}", path))?;
+
+    let mut transaction = description
+        .into_transaction()
+        .with_context(|| format!("Invalid transaction in {}", path))?;
+    if let Some(release) = matches.value_of("release") {
+        transaction.release = Some(release.to_string().into());
+    } else if transaction.release.is_none() {
+        transaction.release = detect_release_name().ok().map(Into::into);
+    }
+    if let Some(environment) = matches.value_of("environment") {
+        transaction.environment = Some(environment.to_string().into());
+    } else if transaction.environment.is_none() {
+        transaction.environment = config.get_environment().map(Into::into);
+    }
+
+    debug!("{:?}", transaction);
+    let id = transaction.event_id;
+    let envelope = Envelope::from(transaction);
+
+    if let Some(ref spool_dir) = spool_dir {
+        match send_or_spool(&dsn, &serialize_envelope(&envelope)?, spool_dir)? {
+            Dispatched::Sent => println!("Transaction sent: {}", id),
+            Dispatched::Spooled(path) => println!(
+                "Transaction could not be sent and was spooled to {}: {}",
+                path.display(),
+                id
+            ),
+        }
+    } else {
+        with_sentry_client(dsn, |c| c.send_envelope(envelope));
+        println!("Transaction dispatched: {}", id);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+
+use crate::commands::sourcemaps::upload::{process_sources, source_args};
+use crate::config::Config;
+use crate::utils::file_upload::UploadContext;
+use crate::utils::sourcemaps::SourceMapProcessor;
+
+pub fn make_command(command: Command) -> Command {
+    source_args(command)
+        .about("Write sourcemaps for a release into an artifact bundle.")
+        .long_about(
+            "Write sourcemaps for a release into an artifact bundle.{n}{n}\
+             Sources are processed the same way as by `sourcemaps upload`, but written to a \
+             local file instead of being uploaded. No credentials are needed. Upload the \
+             bundle later with `sourcemaps upload-bundle`.",
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .value_name("PATH")
+                .required(true)
+                .help("The path to write the artifact bundle to."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let project = config.get_project(matches).ok();
+    let release = config.get_release(matches)?;
+    let output = Path::new(matches.value_of("output").unwrap());
+
+    let mut processor = SourceMapProcessor::new();
+    process_sources(matches, &mut processor)?;
+    processor.write_bundle(
+        &UploadContext {
+            org: &org,
+            project: project.as_deref(),
+            release: &release,
+            dist: matches.value_of("dist"),
+            wait: false,
+        },
+        output,
+    )?;
+
+    println!(
+        "{} Wrote artifact bundle to {}",
+        style(">").dim(),
+        style(output.display()).cyan()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use std::fs;
+
+use anyhow::{Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+
+use crate::utils::args::validate_int;
+use crate::utils::sourcemap_compose::{compose_chain, decode_flat_sourcemap, verify_chain};
+use crate::utils::sourcemaps::{get_debug_id_from_sourcemap, inject_debug_id_into_sourcemap};
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Compose the sourcemaps of a multi-stage build into one sourcemap.")
+        .arg(
+            Arg::new("paths")
+                .value_name("PATHS")
+                .required(true)
+                .multiple_occurrences(true)
+                .help(
+                    "The sourcemaps in build order, starting with the stage that \
+                    reads the original sources.",
+                ),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .value_name("PATH")
+                .required(true)
+                .help("The path to write the composed sourcemap to."),
+        )
+        .arg(
+            Arg::new("samples")
+                .long("samples")
+                .value_name("COUNT")
+                .default_value("100")
+                .validator(validate_int)
+                .help(
+                    "The number of positions that are resolved through every stage \
+                    to verify the composed sourcemap. Use 0 to skip verification.",
+                ),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let paths: Vec<_> = matches.values_of("paths").unwrap().collect();
+    let output = matches.value_of("output").unwrap();
+    let samples: usize = matches.value_of("samples").unwrap().parse()?;
+
+    let mut maps = vec![];
+    let mut debug_id = None;
+    for path in &paths {
+        let contents = fs::read(path).with_context(|| format!("Could not read {}", path))?;
+        maps.push(
+            decode_flat_sourcemap(&contents)
+                .with_context(|| format!("Invalid sourcemap {}", path))?,
+        );
+        // The composed sourcemap belongs to the output of the last stage.
+        debug_id = get_debug_id_from_sourcemap(&contents);
+    }
+
+    let composed = compose_chain(&maps)?;
+    if samples > 0 {
+        let verified = verify_chain(&maps, &composed, samples)?;
+        println!(
+            "{} Verified {} position(s) through {} stage(s)",
+            style(">").dim(),
+            style(verified).yellow(),
+            maps.len()
+        );
+    }
+
+    let mut contents = vec![];
+    composed.to_writer(&mut contents)?;
+    if let Some(debug_id) = debug_id {
+        contents = inject_debug_id_into_sourcemap(&contents, debug_id)?;
+    }
+    fs::write(output, contents).with_context(|| format!("Could not write {}", output))?;
+
+    println!(
+        "{} Composed {} sourcemaps into {}",
+        style(">").dim(),
+        style(maps.len()).yellow(),
+        style(output).cyan()
+    );
+    Ok(())
+}
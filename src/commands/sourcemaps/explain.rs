@@ -1,7 +1,7 @@
-use std::io::Read;
+use std::fs;
 use std::path::Path;
 
-use anyhow::{bail, format_err, Result};
+use anyhow::{bail, format_err, Context, Result};
 use clap::{Arg, ArgMatches, Command};
 use console::style;
 use sentry::protocol::{Frame, Stacktrace};
@@ -10,6 +10,8 @@ use url::Url;
 use crate::api::{Api, Artifact, ProcessedEvent};
 use crate::config::Config;
 use crate::utils::fs::TempFile;
+use crate::utils::local_artifacts::{tilde_url, LocalArtifacts};
+use crate::utils::sourcemaps::join_url;
 use crate::utils::system::QuietExit;
 
 use super::resolve::print_source;
@@ -22,8 +24,43 @@ pub fn make_command(command: Command) -> Command {
             Arg::new("event")
                 .value_name("EVENT_ID")
                 .required(true)
+                .conflicts_with("event_file")
                 .help("ID of an event to be explained."),
         )
+        .arg(
+            Arg::new("event_file")
+                .long("event-file")
+                .value_name("PATH")
+                .requires("artifacts")
+                .help("Read the event from an exported JSON file instead of fetching it."),
+        )
+        .arg(
+            Arg::new("artifacts")
+                .long("artifacts")
+                .short('a')
+                .value_name("PATH")
+                .requires("event_file")
+                .help(
+                    "Check against the files in a local build directory or artifact bundle \
+                    instead of the release artifacts on the server.",
+                ),
+        )
+        .arg(
+            Arg::new("url_prefix")
+                .short('u')
+                .long("url-prefix")
+                .value_name("PREFIX")
+                .requires("artifacts")
+                .help("The URL prefix of the files in the build directory. Defaults to `~`."),
+        )
+        .arg(
+            Arg::new("dist")
+                .long("dist")
+                .short('d')
+                .value_name("DISTRIBUTION")
+                .requires("artifacts")
+                .help("The distribution the files in the build directory would be uploaded with."),
+        )
         .arg(
             Arg::new("force")
                 .long("force")
@@ -60,6 +97,16 @@ where
     println!("{}", style(format!("✖ {}", msg)).red());
 }
 
+/// Where the release artifacts of an event are looked up.
+enum ArtifactSource<'a> {
+    Server {
+        org: &'a str,
+        project: &'a str,
+        release: &'a str,
+    },
+    Local(&'a LocalArtifacts),
+}
+
 fn fetch_event(org: &str, project: &str, event_id: &str) -> Result<ProcessedEvent> {
     match Api::current().get_event(org, Some(project), event_id)? {
         Some(event) => {
@@ -106,15 +153,66 @@ fn extract_top_frame(stacktrace: &Stacktrace) -> Result<&Frame> {
     Ok(top_frame)
 }
 
-fn fetch_release_artifacts(org: &str, project: &str, release: &str) -> Result<Vec<Artifact>> {
-    Api::current().list_release_files(org, Some(project), release).map(|artifacts| {
-        if artifacts.is_empty() {
-            error("Release has no artifacts uploaded");
-            tip("https://docs.sentry.io/platforms/javascript/sourcemaps/troubleshooting_js/#verify-artifacts-are-uploaded");
-            return Err(QuietExit(1).into());
+fn read_event(path: &str) -> Result<ProcessedEvent> {
+    let contents = fs::read(path).with_context(|| format!("Could not read {}", path))?;
+    match serde_json::from_slice::<ProcessedEvent>(&contents) {
+        Ok(event) => {
+            success(format!("Read data for event: {}", event.event_id.simple()));
+            Ok(event)
         }
-        Ok(artifacts)
-    })?
+        Err(err) => {
+            error(format!("Could not parse event from {}: {}", path, err));
+            tip("Export the event as JSON from the event details page.");
+            Err(QuietExit(1).into())
+        }
+    }
+}
+
+fn verify_local_release(artifacts: &LocalArtifacts, release: &str) -> Result<()> {
+    match artifacts.release {
+        Some(ref bundle_release) if bundle_release != release => {
+            error(format!(
+                "Release mismatch. Event: {}, Artifacts: {}",
+                release, bundle_release
+            ));
+            tip("Configure 'release' option in the SDK to match the one used to create the artifact bundle.");
+            Err(QuietExit(1).into())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn fetch_release_artifacts(source: &ArtifactSource<'_>) -> Result<Vec<Artifact>> {
+    let artifacts = match *source {
+        ArtifactSource::Server {
+            org,
+            project,
+            release,
+        } => Api::current().list_release_files(org, Some(project), release)?,
+        ArtifactSource::Local(local) => local
+            .artifacts
+            .iter()
+            .enumerate()
+            .map(|(idx, artifact)| Artifact {
+                id: idx.to_string(),
+                sha1: String::new(),
+                name: artifact.url.clone(),
+                size: artifact.contents.len() as u64,
+                dist: local.dist.clone(),
+                headers: artifact.headers.iter().cloned().collect(),
+            })
+            .collect(),
+    };
+
+    if artifacts.is_empty() {
+        match source {
+            ArtifactSource::Server { .. } => error("Release has no artifacts uploaded"),
+            ArtifactSource::Local(_) => error("No artifacts found in the build directory"),
+        }
+        tip("https://docs.sentry.io/platforms/javascript/sourcemaps/troubleshooting_js/#verify-artifacts-are-uploaded");
+        return Err(QuietExit(1).into());
+    }
+    Ok(artifacts)
 }
 
 // Try to find an artifact which matches the path part of the url extracted from the stacktrace frame,
@@ -188,12 +286,37 @@ fn verify_dists_matches(artifact: &Artifact, dist: Option<&str>) -> Result<()> {
     Ok(())
 }
 
-fn fetch_release_artifact_file(
-    org: &str,
-    project: &str,
-    release: &str,
-    artifact: &Artifact,
-) -> Result<TempFile> {
+// Sourcemap references are relative to the minified file, but references relative to the
+// root have always been accepted here as well. Prefer the former if such an artifact exists.
+fn resolve_sourcemap_url(artifacts: &[Artifact], minified_url: &str, location: &str) -> String {
+    if let Ok(url) = join_url(minified_url, location) {
+        let filename = tilde_url(&url);
+        if artifacts.iter().any(|a| Some(&a.name) == filename.as_ref()) {
+            return url;
+        }
+    }
+    location.to_string()
+}
+
+fn fetch_release_artifact_file(source: &ArtifactSource<'_>, artifact: &Artifact) -> Result<Vec<u8>> {
+    let (org, project, release) = match *source {
+        ArtifactSource::Server {
+            org,
+            project,
+            release,
+        } => (org, project, release),
+        ArtifactSource::Local(local) => {
+            let local_artifact = local
+                .get(&artifact.name)
+                .ok_or_else(|| format_err!("Could not read file {}", artifact.name))?;
+            success(format!(
+                "Successfully read {} file from {}.",
+                artifact.name, local_artifact.location
+            ));
+            return Ok(local_artifact.contents.clone());
+        }
+    };
+
     let api = Api::current();
     let file = TempFile::create()?;
 
@@ -209,7 +332,7 @@ fn fetch_release_artifact_file(
             "Successfully fetched {} file from the server.",
             artifact.name
         ));
-        Ok(file)
+        Ok(fs::read(file.path())?)
     })
     .map_err(|err| {
         format_err!(
@@ -241,13 +364,16 @@ fn fetch_release_artifact_file_metadata(
 }
 
 // https://github.com/getsentry/sentry/blob/623c2f5f3313e6dc55e08e2ae2b11d8f90cdbece/src/sentry/lang/javascript/processor.py#L145-L207
-fn discover_sourcemaps_location(
-    org: &str,
-    project: &str,
-    release: &str,
-    artifact: &Artifact,
-) -> Result<String> {
-    let file_metadata = fetch_release_artifact_file_metadata(org, project, release, artifact)?;
+fn discover_sourcemaps_location(source: &ArtifactSource<'_>, artifact: &Artifact) -> Result<String> {
+    let file_metadata = match *source {
+        ArtifactSource::Server {
+            org,
+            project,
+            release,
+        } => fetch_release_artifact_file_metadata(org, project, release, artifact)?,
+        // Local artifacts carry their headers already.
+        ArtifactSource::Local(_) => artifact.clone(),
+    };
 
     if let Some(header) = file_metadata.headers.get("Sourcemap") {
         return Ok(header.to_owned());
@@ -257,11 +383,8 @@ fn discover_sourcemaps_location(
         return Ok(header.to_owned());
     }
 
-    let file = fetch_release_artifact_file(org, project, release, artifact)?;
-
-    let mut f = file.open()?;
-    let mut buffer = String::new();
-    f.read_to_string(&mut buffer)?;
+    let file = fetch_release_artifact_file(source, artifact)?;
+    let buffer = String::from_utf8_lossy(&file);
 
     for line in buffer.lines().rev() {
         if line.starts_with("//# sourceMappingURL=") || line.starts_with("//@ sourceMappingURL=") {
@@ -276,18 +399,19 @@ fn discover_sourcemaps_location(
     Err(format_err!("Failed to discover source map url"))
 }
 
-fn print_sourcemap(file: &TempFile, line: u32, column: u32) -> Result<()> {
-    let mut f = file.open()?;
-    let mut buf = vec![];
-    f.read_to_end(&mut buf)?;
-    let sm = sourcemap::decode_slice(&buf)?;
+fn print_sourcemap(file: &[u8], line: u32, column: u32) -> Result<()> {
+    let sm = sourcemap::decode_slice(file)?;
 
     if let Some(token) = sm.lookup_token(line, column) {
         if let Some(view) = token.get_source_view() {
             success("Sourcemap position resolves to:");
             print_source(&token, view);
         } else if token.get_source_view().is_none() {
-            bail!("cannot find source");
+            bail!(
+                "Sourcemap has no source content for {}. Make sure the bundler is configured \
+                to include `sourcesContent`.",
+                token.get_source().unwrap_or("[unknown source]")
+            );
         } else {
             bail!("cannot find source for line {} column {}", line, column);
         }
@@ -343,12 +467,33 @@ fn extract_release(event: &ProcessedEvent) -> Result<String> {
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
-    let config = Config::current();
-    let (org, project) = config.get_org_and_project(matches)?;
-    let event_id = matches.value_of("event").unwrap();
-
-    let event = fetch_event(&org, &project, event_id)?;
+    let local_artifacts = match matches.value_of("artifacts") {
+        Some(path) => {
+            let mut artifacts =
+                LocalArtifacts::open(Path::new(path), matches.value_of("url_prefix").unwrap_or("~"))?;
+            if let Some(dist) = matches.value_of("dist") {
+                artifacts.dist = Some(dist.to_string());
+            }
+            Some(artifacts)
+        }
+        None => None,
+    };
+    let org_and_project = match local_artifacts {
+        Some(_) => None,
+        None => Some(Config::current().get_org_and_project(matches)?),
+    };
+
+    let event = match (matches.value_of("event_file"), &org_and_project) {
+        (Some(path), _) => read_event(path)?,
+        (None, Some((org, project))) => {
+            fetch_event(org, project, matches.value_of("event").unwrap())?
+        }
+        (None, None) => unreachable!("--artifacts requires --event-file"),
+    };
     let release = extract_release(&event)?;
+    if let Some(ref local_artifacts) = local_artifacts {
+        verify_local_release(local_artifacts, &release)?;
+    }
 
     if event.exception.values.is_empty() {
         warning("Event has no exception captured, there is no use for source maps");
@@ -393,25 +538,34 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
     }
 
-    let artifacts = fetch_release_artifacts(&org, &project, &release)?;
-    let matched_artifact = find_matching_artifact(&artifacts, frame.abs_path.as_ref().unwrap())?;
+    let source = match (&local_artifacts, &org_and_project) {
+        (Some(local_artifacts), _) => ArtifactSource::Local(local_artifacts),
+        (None, Some((org, project))) => ArtifactSource::Server {
+            org,
+            project,
+            release: &release,
+        },
+        (None, None) => unreachable!(),
+    };
+
+    let abs_path = frame.abs_path.as_ref().unwrap();
+    let artifacts = fetch_release_artifacts(&source)?;
+    let matched_artifact = find_matching_artifact(&artifacts, abs_path)?;
 
     verify_dists_matches(&matched_artifact, event.dist.as_deref())?;
 
     let sourcemap_location =
-        discover_sourcemaps_location(&org, &project, &release, &matched_artifact).map_err(
-            |err| {
-                error(err);
-                QuietExit(1)
-            },
-        )?;
+        discover_sourcemaps_location(&source, &matched_artifact).map_err(|err| {
+            error(err);
+            QuietExit(1)
+        })?;
     success(format!("Found source map location: {}", sourcemap_location));
 
-    let sourcemap_artifact = find_matching_artifact(&artifacts, &sourcemap_location)?;
+    let sourcemap_url = resolve_sourcemap_url(&artifacts, abs_path, &sourcemap_location);
+    let sourcemap_artifact = find_matching_artifact(&artifacts, &sourcemap_url)?;
     verify_dists_matches(&sourcemap_artifact, event.dist.as_deref())?;
 
-    let sourcemap_file =
-        fetch_release_artifact_file(&org, &project, &release, &sourcemap_artifact)?;
+    let sourcemap_file = fetch_release_artifact_file(&source, &sourcemap_artifact)?;
 
     print_sourcemap(
         &sourcemap_file,
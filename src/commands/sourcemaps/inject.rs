@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+
+use crate::utils::file_search::ReleaseFileSearch;
+use crate::utils::sourcemaps::{
+    debug_id_for_pair, find_sourcemap_path, get_debug_id_from_minified,
+    get_debug_id_from_sourcemap, inject_debug_id_into_minified, inject_debug_id_into_sourcemap,
+};
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Inject debug IDs into minified files and their sourcemaps.")
+        .long_about(
+            "Inject debug IDs into minified files and their sourcemaps.{n}{n}\
+             Every minified file that references a sourcemap (or has one next to it with \
+             a `.map` extension) gets a debug ID that is derived from the contents of both \
+             files. The ID is added to the minified file as a runtime snippet and a \
+             `//# debugId=` comment, and to the sourcemap as `debug_id` field. Uploaded \
+             files are then matched by their debug ID instead of their URL.",
+        )
+        .arg(
+            Arg::new("paths")
+                .value_name("PATHS")
+                .required(true)
+                .multiple_occurrences(true)
+                .help("The files or directories to process."),
+        )
+        .arg(
+            Arg::new("ignore")
+                .long("ignore")
+                .short('i')
+                .value_name("IGNORE")
+                .multiple_occurrences(true)
+                .help("Ignores all files and folders matching the given glob"),
+        )
+        .arg(
+            Arg::new("ignore_file")
+                .long("ignore-file")
+                .short('I')
+                .value_name("IGNORE_FILE")
+                .help(
+                    "Ignore all files and folders specified in the given \
+                    ignore file, e.g. .gitignore.",
+                ),
+        )
+        .arg(
+            Arg::new("extensions")
+                .long("ext")
+                .short('x')
+                .value_name("EXT")
+                .multiple_occurrences(true)
+                .help(
+                    "Set the file extensions of minified files to process. \
+                    Specify once per extension.{n}\
+                    Defaults to: `--ext=js --ext=cjs --ext=mjs`",
+                ),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .help("Print the debug IDs without modifying any files."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let dry_run = matches.is_present("dry_run");
+    let extensions = matches
+        .values_of("extensions")
+        .map(|extensions| extensions.map(|ext| ext.trim_start_matches('.')).collect())
+        .unwrap_or_else(|| vec!["js", "cjs", "mjs"]);
+    let ignores: Vec<_> = matches
+        .values_of("ignore")
+        .map(|ignores| ignores.map(|i| format!("!{}", i)).collect())
+        .unwrap_or_default();
+
+    let mut files = vec![];
+    for path in matches.values_of("paths").unwrap() {
+        let path = PathBuf::from(path);
+        if path.is_file() {
+            files.push(ReleaseFileSearch::collect_file(path)?);
+        } else {
+            files.extend(
+                ReleaseFileSearch::new(path)
+                    .ignore_file(matches.value_of("ignore_file").unwrap_or(""))
+                    .ignores(ignores.clone())
+                    .extensions(extensions.clone())
+                    .collect_files()?,
+            );
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    println!("{} Injecting debug IDs", style(">").dim());
+    let mut injected = 0;
+    for file in files {
+        let path = &file.path;
+        let sourcemap_path = match find_sourcemap_path(path, &file.contents) {
+            Some(sourcemap_path) => sourcemap_path,
+            None => {
+                println!("  skipped {} (no sourcemap found)", path.display());
+                continue;
+            }
+        };
+        let sourcemap = fs::read(&sourcemap_path)
+            .with_context(|| format!("Could not read {}", sourcemap_path.display()))?;
+
+        let minified_id = get_debug_id_from_minified(&file.contents);
+        let sourcemap_id = get_debug_id_from_sourcemap(&sourcemap);
+        if let Some(debug_id) = minified_id.filter(|&id| Some(id) == sourcemap_id) {
+            println!(
+                "  skipped {} (already has debug id {})",
+                path.display(),
+                debug_id
+            );
+            continue;
+        }
+
+        let debug_id = minified_id
+            .or(sourcemap_id)
+            .unwrap_or_else(|| debug_id_for_pair(&file.contents, &sourcemap));
+        if !dry_run {
+            if minified_id.is_none() {
+                fs::write(path, inject_debug_id_into_minified(&file.contents, debug_id))
+                    .with_context(|| format!("Could not write {}", path.display()))?;
+            }
+            let sourcemap = inject_debug_id_into_sourcemap(&sourcemap, debug_id)
+                .with_context(|| format!("Invalid sourcemap {}", sourcemap_path.display()))?;
+            fs::write(&sourcemap_path, sourcemap)
+                .with_context(|| format!("Could not write {}", sourcemap_path.display()))?;
+        }
+
+        println!(
+            "  {} {} (sourcemap {})",
+            style(debug_id).cyan(),
+            path.display(),
+            sourcemap_path.display()
+        );
+        injected += 1;
+    }
+
+    println!(
+        "{} {} debug IDs into {} file(s)",
+        style(">").dim(),
+        if dry_run { "Would inject" } else { "Injected" },
+        style(injected).yellow()
+    );
+
+    Ok(())
+}
@@ -3,15 +3,27 @@ use clap::{ArgMatches, Command};
 
 use crate::utils::args::ArgExt;
 
+pub mod bundle;
+pub mod compose;
 pub mod explain;
+pub mod inject;
 pub mod resolve;
+pub mod symbolicate;
 pub mod upload;
+pub mod upload_bundle;
+pub mod validate;
 
 macro_rules! each_subcommand {
     ($mac:ident) => {
+        $mac!(bundle);
+        $mac!(compose);
         $mac!(explain);
+        $mac!(inject);
         $mac!(resolve);
+        $mac!(symbolicate);
         $mac!(upload);
+        $mac!(upload_bundle);
+        $mac!(validate);
     };
 }
 
@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use anyhow::{bail, format_err, Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+use lazy_static::lazy_static;
+use regex::Regex;
+use sentry::protocol::Frame;
+use sourcemap::{DecodedMap, SourceView};
+
+use crate::api::ProcessedEvent;
+use crate::utils::local_artifacts::LocalArtifacts;
+
+use super::resolve::print_source;
+
+lazy_static! {
+    /// V8: `at fn (url:line:col)` or `at url:line:col`.
+    static ref V8_FRAME_RE: Regex = Regex::new(
+        r"^\s*at (?:(?:async )?(?P<function>.+?) \()?(?P<url>\S+?):(?P<line>\d+):(?P<column>\d+)\)?\s*$"
+    )
+    .unwrap();
+    /// SpiderMonkey and JavaScriptCore: `fn@url:line:col`, the column is
+    /// missing in older JavaScriptCore versions.
+    static ref GECKO_FRAME_RE: Regex = Regex::new(
+        r"^\s*(?P<function>[^@\s]*)@(?P<url>\S+?):(?P<line>\d+)(?::(?P<column>\d+))?\s*$"
+    )
+    .unwrap();
+}
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Resolve a JavaScript stack trace or event with local sourcemaps.")
+        .long_about(
+            "Resolve a JavaScript stack trace or event with local sourcemaps.{n}{n}\
+             Reads a stack trace in V8, SpiderMonkey or JavaScriptCore format, or a Sentry \
+             event in JSON format, and resolves every frame to its original location using \
+             the sourcemaps in a build directory or artifact bundle.",
+        )
+        .arg(
+            Arg::new("path")
+                .value_name("PATH")
+                .help("The file with the stack trace or event. Reads from stdin if omitted or `-`."),
+        )
+        .arg(
+            Arg::new("artifacts")
+                .long("artifacts")
+                .short('a')
+                .value_name("PATH")
+                .required(true)
+                .help("The build directory or artifact bundle with minified files and sourcemaps."),
+        )
+        .arg(
+            Arg::new("url_prefix")
+                .short('u')
+                .long("url-prefix")
+                .value_name("PREFIX")
+                .default_value("~")
+                .help("The URL prefix of the files in the build directory."),
+        )
+}
+
+/// A frame of a stack trace that refers to a minified file.
+struct StackFrame {
+    function: Option<String>,
+    url: String,
+    line: u32,
+    column: Option<u32>,
+}
+
+impl StackFrame {
+    fn parse(line: &str) -> Option<StackFrame> {
+        let caps = V8_FRAME_RE
+            .captures(line)
+            .or_else(|| GECKO_FRAME_RE.captures(line))?;
+        Some(StackFrame {
+            function: caps
+                .name("function")
+                .map(|m| m.as_str().to_string())
+                .filter(|f| !f.is_empty()),
+            url: caps["url"].to_string(),
+            line: caps["line"].parse().ok()?,
+            column: caps.name("column").and_then(|m| m.as_str().parse().ok()),
+        })
+    }
+
+    fn from_event_frame(frame: &Frame) -> Option<StackFrame> {
+        Some(StackFrame {
+            function: frame.function.clone(),
+            url: frame.abs_path.clone()?,
+            line: frame.lineno? as u32,
+            column: frame.colno.map(|c| c as u32),
+        })
+    }
+
+    fn location(&self) -> String {
+        match self.column {
+            Some(column) => format!("{}:{}:{}", self.url, self.line, column),
+            None => format!("{}:{}", self.url, self.line),
+        }
+    }
+}
+
+struct Symbolicator<'a> {
+    artifacts: &'a LocalArtifacts,
+    sourcemaps: HashMap<String, DecodedMap>,
+    resolved: usize,
+    total: usize,
+}
+
+impl<'a> Symbolicator<'a> {
+    fn new(artifacts: &'a LocalArtifacts) -> Self {
+        Symbolicator {
+            artifacts,
+            sourcemaps: HashMap::new(),
+            resolved: 0,
+            total: 0,
+        }
+    }
+
+    fn print_frame(&mut self, frame: &StackFrame) {
+        self.total += 1;
+        if let Err(err) = self.resolve_frame(frame) {
+            println!(
+                "  at {} ({})",
+                frame.function.as_deref().unwrap_or("<anonymous>"),
+                frame.location()
+            );
+            println!("    {}", style(format!("not resolved: {}", err)).yellow());
+        }
+    }
+
+    fn resolve_frame(&mut self, frame: &StackFrame) -> Result<()> {
+        let minified = match self.artifacts.find_by_url(&frame.url) {
+            Some(minified) => minified,
+            None => match self.artifacts.find_by_filename(&frame.url) {
+                Some(partial) => bail!(
+                    "no file matches {}, but {} has the same name (check the URL prefix)",
+                    frame.url,
+                    partial.url
+                ),
+                None => bail!("no file matches {}", frame.url),
+            },
+        };
+        let sourcemap = self.artifacts.find_sourcemap(minified)?;
+        if !self.sourcemaps.contains_key(&sourcemap.url) {
+            let decoded = sourcemap::decode_slice(&sourcemap.contents)
+                .with_context(|| format!("invalid sourcemap {}", sourcemap.url))?;
+            self.sourcemaps.insert(sourcemap.url.clone(), decoded);
+        }
+        let sm = &self.sourcemaps[&sourcemap.url];
+
+        let line = frame.line.saturating_sub(1);
+        let column = frame.column.unwrap_or(1).saturating_sub(1);
+        let token = sm
+            .lookup_token(line, column)
+            .ok_or_else(|| format_err!("no mapping for {}:{}", frame.line, column + 1))?;
+
+        let minified_view = String::from_utf8(minified.contents.clone())
+            .ok()
+            .map(SourceView::from_string);
+        let function = sm
+            .get_original_function_name(
+                line,
+                column,
+                frame.function.as_deref(),
+                minified_view.as_ref(),
+            )
+            .or(frame.function.as_deref())
+            .unwrap_or("<anonymous>");
+
+        println!(
+            "  at {} ({}:{}:{})",
+            style(function).bold(),
+            token.get_source().unwrap_or("<unknown>"),
+            token.get_src_line() + 1,
+            token.get_src_col() + 1
+        );
+        if let Some(view) = token.get_source_view() {
+            print_source(&token, view);
+        } else {
+            println!("    {}", style("no source content available").dim());
+        }
+        self.resolved += 1;
+        Ok(())
+    }
+}
+
+fn read_input(path: Option<&str>) -> Result<String> {
+    match path {
+        None | Some("-") => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+        Some(path) => fs::read_to_string(path).with_context(|| format!("Could not read {}", path)),
+    }
+}
+
+fn symbolicate_event(symbolicator: &mut Symbolicator<'_>, event: &ProcessedEvent) {
+    for exception in event.exception.values.iter().rev() {
+        match exception.value {
+            Some(ref value) => println!("{}: {}", exception.ty, value),
+            None => println!("{}", exception.ty),
+        }
+        let stacktrace = exception
+            .raw_stacktrace
+            .as_ref()
+            .or(exception.stacktrace.as_ref());
+        // Sentry stores frames oldest first, stack traces print them newest first.
+        for frame in stacktrace.iter().flat_map(|s| s.frames.iter().rev()) {
+            match StackFrame::from_event_frame(frame) {
+                Some(frame) => symbolicator.print_frame(&frame),
+                None => println!(
+                    "  at {} (<unknown location>)",
+                    frame.function.as_deref().unwrap_or("<anonymous>")
+                ),
+            }
+        }
+    }
+}
+
+fn symbolicate_stacktrace(symbolicator: &mut Symbolicator<'_>, input: &str) {
+    for line in input.lines() {
+        match StackFrame::parse(line) {
+            Some(frame) => symbolicator.print_frame(&frame),
+            None => println!("{}", line),
+        }
+    }
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let artifacts = LocalArtifacts::open(
+        Path::new(matches.value_of("artifacts").unwrap()),
+        matches.value_of("url_prefix").unwrap(),
+    )?;
+    let input = read_input(matches.value_of("path"))?;
+
+    let mut symbolicator = Symbolicator::new(&artifacts);
+    if input.trim_start().starts_with('{') {
+        let event: ProcessedEvent =
+            serde_json::from_str(&input).context("Could not parse event")?;
+        symbolicate_event(&mut symbolicator, &event);
+    } else {
+        symbolicate_stacktrace(&mut symbolicator, &input);
+    }
+
+    println!();
+    println!(
+        "{} Resolved {} of {} frame(s)",
+        style(">").dim(),
+        style(symbolicator.resolved).yellow(),
+        symbolicator.total
+    );
+    Ok(())
+}
+
+#[test]
+fn test_parse_stack_frame() {
+    let frame = StackFrame::parse("    at bar (http://localhost/dist/app.min.js:1:40)").unwrap();
+    assert_eq!(frame.function.as_deref(), Some("bar"));
+    assert_eq!(frame.url, "http://localhost/dist/app.min.js");
+    assert_eq!((frame.line, frame.column), (1, Some(40)));
+
+    let frame = StackFrame::parse("    at http://localhost/dist/app.min.js:1:53").unwrap();
+    assert_eq!(frame.function, None);
+    assert_eq!(frame.column, Some(53));
+
+    let frame = StackFrame::parse("    at async Promise.all (index 0)");
+    assert!(frame.is_none());
+
+    let frame = StackFrame::parse("r@http://localhost/dist/app.min.js:1:40").unwrap();
+    assert_eq!(frame.function.as_deref(), Some("r"));
+    assert_eq!(frame.url, "http://localhost/dist/app.min.js");
+
+    let frame = StackFrame::parse("@http://localhost/dist/app.min.js:1").unwrap();
+    assert_eq!(frame.function, None);
+    assert_eq!((frame.line, frame.column), (1, None));
+
+    assert!(StackFrame::parse("Error: whoops").is_none());
+}
@@ -1,8 +1,10 @@
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use anyhow::{format_err, Result};
+use anyhow::{bail, format_err, Result};
 use clap::{Arg, ArgMatches, Command};
+use console::style;
 use glob::{glob_with, MatchOptions};
 use log::{debug, warn};
 use sha1_smol::Digest;
@@ -12,20 +14,32 @@ use crate::config::Config;
 use crate::utils::file_search::ReleaseFileSearch;
 use crate::utils::file_upload::UploadContext;
 use crate::utils::fs::path_as_url;
+use crate::utils::hermes::is_hermes_bytecode;
+use crate::utils::logging::is_quiet_mode;
+use crate::utils::sourcemap_detect::detect_build;
+use crate::utils::sourcemap_optimize::OptimizeOptions;
+use crate::utils::sourcemap_sources::{SourceResolver, SourceRoot};
 use crate::utils::sourcemaps::SourceMapProcessor;
 
 pub fn make_command(command: Command) -> Command {
-    command
+    source_args(command)
         .about("Upload sourcemaps for a release.")
         // Backward compatibility with `releases files <VERSION>` commands.
         .arg(Arg::new("version").long("version").hide(true))
         .arg(
-            Arg::new("paths")
-                .value_name("PATHS")
-                .required_unless_present_any(&["bundle", "bundle_sourcemap"])
-                .multiple_occurrences(true)
-                .help("The files to upload."),
+            Arg::new("wait")
+                .long("wait")
+                .help("Wait for the server to fully process uploaded files."),
         )
+}
+
+/// Adds the arguments that select and process sources, shared with
+/// `sourcemaps bundle`.
+pub fn source_args(command: Command) -> Command {
+    path_args(command)
+        .mut_arg("paths", |arg| {
+            arg.required_unless_present_any(["bundle", "bundle_sourcemap"])
+        })
         .arg(
             Arg::new("url_prefix")
                 .short('u')
@@ -56,11 +70,6 @@ pub fn make_command(command: Command) -> Command {
                 .long("decompress")
                 .help("Enable files gzip decompression prior to upload."),
         )
-        .arg(
-            Arg::new("wait")
-                .long("wait")
-                .help("Wait for the server to fully process uploaded files."),
-        )
         .arg(
             Arg::new("no_sourcemap_reference")
                 .long("no-sourcemap-reference")
@@ -81,6 +90,85 @@ pub fn make_command(command: Command) -> Command {
                 setups like react-native that generate sourcemaps that \
                 would otherwise not work for sentry.",
         ))
+        .arg(
+            Arg::new("detect")
+                .long("detect")
+                .conflicts_with_all(&["bundle", "ignore", "ignore_file", "extensions"])
+                .help(
+                    "Detect the build output in the given paths.{n}\
+                    Supports webpack stats files, Vite and Rollup manifests, Next.js \
+                    builds and esbuild metafiles. The files and URL prefix are derived \
+                    from the build unless --url-prefix is given.",
+                ),
+        )
+        .arg(
+            Arg::new("compose")
+                .long("compose")
+                .conflicts_with("bundle")
+                .help(
+                    "Compose sourcemaps of multi-stage builds.{n}\
+                    Sources that are themselves build outputs with a sourcemap are \
+                    resolved through that sourcemap, so that the uploaded sourcemaps \
+                    point to the original sources.",
+                ),
+        )
+        .arg(
+            Arg::new("source_root")
+                .long("source-root")
+                .value_name("PREFIX=DIR")
+                .multiple_occurrences(true)
+                .validator(|value| value.parse::<SourceRoot>())
+                .conflicts_with("no_rewrite")
+                .help(
+                    "Read sources starting with PREFIX from the local directory DIR \
+                    when inlining sources that are missing from `sourcesContent`. \
+                    Can be specified multiple times.",
+                ),
+        )
+        .arg(
+            Arg::new("source_commit")
+                .long("source-commit")
+                .value_name("REV")
+                .conflicts_with("no_rewrite")
+                .help(
+                    "Read sources that are not found on disk from this commit of the \
+                    git repository in the current directory.",
+                ),
+        )
+        .arg(
+            Arg::new("optimize")
+                .long("optimize")
+                .conflicts_with("bundle")
+                .help(
+                    "Reduce the size of sourcemaps before upload.{n}\
+                    Drops names and sources that no mapping refers to and uploads \
+                    sources content shared by several sourcemaps only once, unless a \
+                    different file with the same URL is uploaded or already in the \
+                    release. Prints the size saved per file.",
+                ),
+        )
+        .arg(
+            Arg::new("strip_sources")
+                .long("strip-sources")
+                .conflicts_with("bundle")
+                .value_name("GLOB")
+                .multiple_occurrences(true)
+                .help(
+                    "Remove the mappings to sources matching the glob from sourcemaps, \
+                    e.g. '*/node_modules/*'. Implies --optimize.",
+                ),
+        )
+        .arg(
+            Arg::new("strip_sources_content")
+                .long("strip-sources-content")
+                .conflicts_with("bundle")
+                .value_name("GLOB")
+                .multiple_occurrences(true)
+                .help(
+                    "Remove the content of sources matching the glob from sourcemaps, \
+                    keeping their mappings. Implies --optimize.",
+                ),
+        )
         .arg(
             Arg::new("strip_prefix")
                 .long("strip-prefix")
@@ -105,6 +193,48 @@ pub fn make_command(command: Command) -> Command {
                 )
                 .conflicts_with("no_rewrite"),
         )
+        .arg(
+            Arg::new("bundle")
+                .long("bundle")
+                .value_name("BUNDLE")
+                .conflicts_with("paths")
+                .requires_all(&["bundle_sourcemap"])
+                .help("Path to the application bundle (indexed, file, or regular)"),
+        )
+        .arg(
+            Arg::new("bundle_sourcemap")
+                .long("bundle-sourcemap")
+                .value_name("BUNDLE_SOURCEMAP")
+                .conflicts_with("paths")
+                .requires_all(&["bundle"])
+                .help("Path to the bundle sourcemap"),
+        )
+        .arg(
+            Arg::new("hermes_sourcemap")
+                .long("hermes-sourcemap")
+                .value_name("HERMES_SOURCEMAP")
+                .requires_all(&["bundle", "bundle_sourcemap"])
+                .help(
+                    "Path to the sourcemap emitted by the hermes compiler for a bytecode \
+                    bundle. It is composed with the packager sourcemap given by \
+                    --bundle-sourcemap.",
+                ),
+        )
+        // Legacy flag that has no effect, left hidden for backward compatibility
+        .arg(Arg::new("rewrite").long("rewrite").hide(true))
+        // Legacy flag that has no effect, left hidden for backward compatibility
+        .arg(Arg::new("verbose").long("verbose").short('v').hide(true))
+}
+
+/// Adds the arguments read by `add_sources_from_paths`.
+pub fn path_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("paths")
+                .value_name("PATHS")
+                .multiple_occurrences(true)
+                .help("The files to upload."),
+        )
         .arg(
             Arg::new("ignore")
                 .long("ignore")
@@ -123,22 +253,6 @@ pub fn make_command(command: Command) -> Command {
                     ignore file, e.g. .gitignore.",
                 ),
         )
-        .arg(
-            Arg::new("bundle")
-                .long("bundle")
-                .value_name("BUNDLE")
-                .conflicts_with("paths")
-                .requires_all(&["bundle_sourcemap"])
-                .help("Path to the application bundle (indexed, file, or regular)"),
-        )
-        .arg(
-            Arg::new("bundle_sourcemap")
-                .long("bundle-sourcemap")
-                .value_name("BUNDLE_SOURCEMAP")
-                .conflicts_with("paths")
-                .requires_all(&["bundle"])
-                .help("Path to the bundle sourcemap"),
-        )
         .arg(
             Arg::new("extensions")
                 .long("ext")
@@ -152,10 +266,6 @@ pub fn make_command(command: Command) -> Command {
                     Defaults to: `--ext=js --ext=map --ext=jsbundle --ext=bundle`",
                 ),
         )
-        // Legacy flag that has no effect, left hidden for backward compatibility
-        .arg(Arg::new("rewrite").long("rewrite").hide(true))
-        // Legacy flag that has no effect, left hidden for backward compatibility
-        .arg(Arg::new("verbose").long("verbose").short('v').hide(true))
 }
 
 fn get_prefixes_from_args(matches: &ArgMatches) -> Vec<&str> {
@@ -169,6 +279,22 @@ fn get_prefixes_from_args(matches: &ArgMatches) -> Vec<&str> {
     prefixes
 }
 
+/// Inlines missing source contents using the source root and commit arguments.
+fn inline_sources(matches: &ArgMatches, processor: &mut SourceMapProcessor) -> Result<()> {
+    if !matches.is_present("source_root") && !matches.is_present("source_commit") {
+        return Ok(());
+    }
+
+    let mut resolver = SourceResolver::new();
+    if let Some(roots) = matches.values_of("source_root") {
+        resolver.source_roots(roots.map(|root| root.parse()).collect::<Result<Vec<_>>>()?);
+    }
+    if let Some(rev) = matches.value_of("source_commit") {
+        resolver.git_commit(rev)?;
+    }
+    processor.inline_sources(&resolver)
+}
+
 fn process_sources_from_bundle(
     matches: &ArgMatches,
     processor: &mut SourceMapProcessor,
@@ -216,23 +342,32 @@ fn process_sources_from_bundle(
         processor.unpack_ram_bundle(&ram_bundle, &bundle_url)?;
     } else if sourcemap::ram_bundle::RamBundle::parse_indexed_from_path(&bundle_path).is_ok() {
         debug!("Indexed RAM bundle found");
+    } else if matches!(fs::read(&bundle_path), Ok(contents) if is_hermes_bytecode(&contents)) {
+        debug!("Hermes bytecode bundle found");
     } else {
         warn!("Regular bundle found");
     }
 
+    if let Some(hermes_sourcemap) = matches.value_of("hermes_sourcemap") {
+        processor.compose_hermes_sourcemap(&sourcemap_url, Path::new(hermes_sourcemap))?;
+    }
+
     let mut prefixes = get_prefixes_from_args(matches);
     if !prefixes.contains(&"~") {
         prefixes.push("~");
     }
     debug!("Prefixes: {:?}", prefixes);
 
+    inline_sources(matches, processor)?;
     processor.rewrite(&prefixes)?;
     processor.add_sourcemap_references()?;
 
     Ok(())
 }
 
-fn process_sources_from_paths(
+/// Adds the files matched by the paths, extensions and ignore arguments to
+/// the processor without processing them.
+pub fn add_sources_from_paths(
     matches: &ArgMatches,
     processor: &mut SourceMapProcessor,
 ) -> Result<()> {
@@ -288,11 +423,99 @@ fn process_sources_from_paths(
         }
     }
 
+    Ok(())
+}
+
+/// Adds the files of the builds detected in the paths to the processor.
+fn add_detected_sources(matches: &ArgMatches, processor: &mut SourceMapProcessor) -> Result<()> {
+    let url_suffix = matches.value_of("url_suffix").unwrap_or("");
+
+    for path in matches.values_of("paths").unwrap() {
+        let build = detect_build(Path::new(path))?.ok_or_else(|| {
+            format_err!(
+                "Could not detect a supported build in {}. Point to the build \
+                 directory or manifest, or upload without --detect.",
+                path
+            )
+        })?;
+        if build.files.is_empty() {
+            bail!(
+                "No minified files or sourcemaps found for the {} build in {}",
+                build.tool,
+                build.source.display()
+            );
+        }
+
+        let url_prefix = matches
+            .value_of("url_prefix")
+            .map(|prefix| prefix.strip_suffix('/').unwrap_or(prefix))
+            .unwrap_or(&build.url_prefix);
+
+        if !is_quiet_mode() {
+            println!(
+                "{} Detected {} build from {}",
+                style(">").dim(),
+                style(build.tool).yellow(),
+                style(build.source.display()).cyan()
+            );
+            println!("  Output directory: {}", build.output_dir.display());
+            println!("  URL prefix: {}", url_prefix);
+        }
+
+        for file in &build.files {
+            let url = format!(
+                "{}/{}{}",
+                url_prefix,
+                path_as_url(build.relative_path(file)),
+                url_suffix
+            );
+            if !is_quiet_mode() {
+                println!("    {} -> {}", url, file.display());
+            }
+            processor.add(&url, ReleaseFileSearch::collect_file(file.clone())?)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn process_sources_from_paths(
+    matches: &ArgMatches,
+    processor: &mut SourceMapProcessor,
+) -> Result<()> {
+    if matches.is_present("detect") {
+        add_detected_sources(matches, processor)?;
+    } else {
+        add_sources_from_paths(matches, processor)?;
+    }
+
+    if matches.is_present("compose") {
+        processor.compose()?;
+    }
+
     if !matches.is_present("no_rewrite") {
+        inline_sources(matches, processor)?;
         let prefixes = get_prefixes_from_args(matches);
         processor.rewrite(&prefixes)?;
     }
 
+    if matches.is_present("optimize")
+        || matches.is_present("strip_sources")
+        || matches.is_present("strip_sources_content")
+    {
+        let mut options = OptimizeOptions::new();
+        options
+            .strip_sources(matches.values_of("strip_sources").into_iter().flatten())?
+            .strip_sources_content(
+                matches
+                    .values_of("strip_sources_content")
+                    .into_iter()
+                    .flatten(),
+            )?
+            .dedupe_sources_content(true);
+        processor.optimize(&options)?;
+    }
+
     if !matches.is_present("no_sourcemap_reference") {
         processor.add_sourcemap_references()?;
     }
@@ -304,6 +527,15 @@ fn process_sources_from_paths(
     Ok(())
 }
 
+/// Collects and processes the sources selected by `source_args`.
+pub fn process_sources(matches: &ArgMatches, processor: &mut SourceMapProcessor) -> Result<()> {
+    if matches.is_present("bundle") && matches.is_present("bundle_sourcemap") {
+        process_sources_from_bundle(matches, processor)
+    } else {
+        process_sources_from_paths(matches, processor)
+    }
+}
+
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let version = config.get_release_with_legacy_fallback(matches)?;
@@ -311,12 +543,6 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let api = Api::current();
     let mut processor = SourceMapProcessor::new();
 
-    if matches.is_present("bundle") && matches.is_present("bundle_sourcemap") {
-        process_sources_from_bundle(matches, &mut processor)?;
-    } else {
-        process_sources_from_paths(matches, &mut processor)?;
-    }
-
     // make sure the release exists
     let release = api.new_release(
         &org,
@@ -327,13 +553,20 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         },
     )?;
 
+    // The files of the release are needed before processing, so that
+    // --optimize keeps sources content that differs from them.
     for artifact in api.list_release_files(&org, Some(&project), &release.version)? {
         let checksum = Digest::from_str(&artifact.sha1)
             .map_err(|_| format_err!("Invalid artifact checksum"))?;
 
+        if artifact.dist.as_deref() == matches.value_of("dist") {
+            processor.add_release_file(&artifact.name, checksum);
+        }
         processor.add_already_uploaded_source(checksum);
     }
 
+    process_sources(matches, &mut processor)?;
+
     processor.upload(&UploadContext {
         org: &org,
         project: Some(&project),
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::{Api, ChunkUploadCapability, NewRelease};
+use crate::config::Config;
+use crate::utils::file_upload::{
+    read_artifact_bundle_attributes, upload_artifact_bundle, UploadContext,
+};
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Upload an artifact bundle created with `sourcemaps bundle`.")
+        .long_about(
+            "Upload an artifact bundle created with `sourcemaps bundle`.{n}{n}\
+             The organization, project, release and distribution are read from the bundle. \
+             If they are passed explicitly, they must match the bundle.",
+        )
+        .arg(
+            Arg::new("path")
+                .value_name("PATH")
+                .required(true)
+                .help("The path to the artifact bundle."),
+        )
+        .arg(
+            Arg::new("wait")
+                .long("wait")
+                .help("Wait for the server to fully process uploaded files."),
+        )
+}
+
+/// Returns the value of a bundle attribute, or the configured default if the
+/// bundle does not have it. Values passed explicitly on the command line must
+/// match the bundle.
+fn resolve_attribute(
+    attributes: &BTreeMap<String, String>,
+    matches: &ArgMatches,
+    key: &str,
+    default: Option<String>,
+) -> Result<Option<String>> {
+    let value = match attributes.get(key) {
+        Some(value) => value,
+        None => return Ok(default),
+    };
+    if let Some(explicit) = matches
+        .values_of(key)
+        .into_iter()
+        .flatten()
+        .find(|explicit| explicit != value)
+    {
+        bail!(
+            "The bundle was created for {} {}, but {} was given",
+            key,
+            value,
+            explicit
+        );
+    }
+    Ok(Some(value.clone()))
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let path = Path::new(matches.value_of("path").unwrap());
+    let attributes = read_artifact_bundle_attributes(path)?;
+
+    let org = match resolve_attribute(&attributes, matches, "org", config.get_org(matches).ok())? {
+        Some(org) => org,
+        None => bail!("An organization slug is required (provide with --org)"),
+    };
+    let release = match resolve_attribute(
+        &attributes,
+        matches,
+        "release",
+        config.get_release(matches).ok(),
+    )? {
+        Some(release) => release,
+        None => bail!("A release slug is required (provide with --release)"),
+    };
+    let project = resolve_attribute(
+        &attributes,
+        matches,
+        "project",
+        config.get_project(matches).ok(),
+    )?;
+
+    let api = Api::current();
+    let chunk_options = match api.get_chunk_upload_options(&org)? {
+        Some(options) if options.supports(ChunkUploadCapability::ReleaseFiles) => options,
+        _ => bail!("The Sentry server does not support artifact bundle uploads"),
+    };
+
+    // make sure the release exists
+    let release = api.new_release(
+        &org,
+        &NewRelease {
+            version: release,
+            projects: project.iter().cloned().collect(),
+            ..Default::default()
+        },
+    )?;
+
+    upload_artifact_bundle(
+        &UploadContext {
+            org: &org,
+            project: project.as_deref(),
+            release: &release.version,
+            dist: attributes.get("dist").map(String::as_str),
+            wait: matches.is_present("wait"),
+        },
+        path,
+        &chunk_options,
+    )
+}
@@ -0,0 +1,105 @@
+use std::fs::File;
+use std::io;
+
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+
+use crate::utils::file_upload::LogLevel;
+use crate::utils::logging::{is_quiet_mode, set_quiet_mode};
+use crate::utils::sourcemap_validation::ReportFormat;
+use crate::utils::sourcemaps::SourceMapProcessor;
+
+use super::upload::{add_sources_from_paths, path_args};
+
+pub fn make_command(command: Command) -> Command {
+    path_args(command)
+        .about("Validate sourcemaps and write a validation report.")
+        .mut_arg("paths", |arg| {
+            arg.required(true)
+                .help("The files or directories to validate.")
+        })
+        .arg(
+            Arg::new("url_prefix")
+                .short('u')
+                .long("url-prefix")
+                .value_name("PREFIX")
+                .help("The URL prefix to prepend to all filenames."),
+        )
+        .arg(
+            Arg::new("url_suffix")
+                .long("url-suffix")
+                .value_name("SUFFIX")
+                .help("The URL suffix to append to all filenames."),
+        )
+        .arg(
+            Arg::new("decompress")
+                .long("decompress")
+                .help("Enable files gzip decompression prior to validation."),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .possible_values(["text", "json", "junit"])
+                .default_value("text")
+                .help("The format of the validation report."),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .value_name("PATH")
+                .help("Write the report to a file instead of stdout."),
+        )
+        .arg(
+            Arg::new("fail_on")
+                .long("fail-on")
+                .value_name("LEVEL")
+                .possible_values(["error", "warning", "never"])
+                .default_value("error")
+                .help("Exit with an error if any problem of this severity or higher is found."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let format: ReportFormat = matches.value_of("format").unwrap().parse()?;
+    let fail_level = match matches.value_of("fail_on").unwrap() {
+        "error" => Some(LogLevel::Error),
+        "warning" => Some(LogLevel::Warning),
+        _ => None,
+    };
+    let output = matches.value_of("output");
+
+    // Keep machine readable reports on stdout free of progress output.
+    if format != ReportFormat::Text && output.is_none() {
+        set_quiet_mode(true);
+    }
+
+    let mut processor = SourceMapProcessor::new();
+    add_sources_from_paths(matches, &mut processor)?;
+    let report = processor.validation_report();
+
+    match output {
+        Some(path) => {
+            let file = File::create(path).with_context(|| format!("Could not create {}", path))?;
+            report.write(file, format, fail_level)?;
+            if !is_quiet_mode() {
+                println!(
+                    "{} Wrote validation report to {}",
+                    style(">").dim(),
+                    style(path).cyan()
+                );
+            }
+        }
+        None => report.write(io::stdout(), format, fail_level)?,
+    }
+
+    if matches!(fail_level, Some(level) if report.has_issues(level)) {
+        bail!(
+            "Sourcemap validation failed with {} error(s) and {} warning(s).",
+            report.errors,
+            report.warnings
+        );
+    }
+    Ok(())
+}
@@ -415,6 +415,15 @@ impl Config {
         }
     }
 
+    /// Return the directory failed envelopes are spooled to
+    pub fn get_spool_dir(&self) -> Option<PathBuf> {
+        if let Some(val) = env::var_os("SENTRY_SPOOL_DIR") {
+            Some(PathBuf::from(val))
+        } else {
+            self.ini.get_from(Some("spool"), "dir").map(PathBuf::from)
+        }
+    }
+
     /// Return the environment
     pub fn get_environment(&self) -> Option<String> {
         if env::var_os("SENTRY_ENVIRONMENT").is_some() {
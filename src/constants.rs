@@ -36,6 +36,8 @@ pub const DEFAULT_INITIAL_INTERVAL: u64 = 1000;
 pub const DEFAULT_MAX_INTERVAL: u64 = 5000;
 /// Default number of retry attempts
 pub const DEFAULT_RETRIES: u32 = 5;
+/// Seconds to wait after a rate limited request without a retry delay.
+pub const DEFAULT_RETRY_AFTER: u64 = 60;
 /// Default maximum file size of DIF uploads.
 pub const DEFAULT_MAX_DIF_SIZE: u64 = 2 * 1024 * 1024 * 1024; // 2GB
 /// Default maximum file size of a single file inside DIF bundle.
@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
-use anyhow::{bail, Result};
-use chrono::{DateTime, TimeZone, Utc};
+use anyhow::{bail, format_err, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use clap::{Arg, Command};
 use symbolic::common::DebugId;
 use uuid::Uuid;
@@ -94,11 +94,42 @@ pub fn get_timestamp(value: &str) -> Result<DateTime<Utc>> {
     }
 }
 
+pub fn validate_duration(v: &str) -> Result<(), String> {
+    if let Err(err) = get_duration(v) {
+        Err(err.to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Parses a duration like `90d`, with one of the units `s`, `m`, `h`, `d` or `w`.
+pub fn get_duration(value: &str) -> Result<Duration> {
+    let split = value.len() - value.chars().last().map_or(0, char::len_utf8);
+    let (amount, unit) = value.split_at(split);
+    let amount = match amount.parse::<i64>() {
+        Ok(amount) if amount >= 0 => amount,
+        _ => bail!("Invalid duration. A number followed by s, m, h, d or w expected."),
+    };
+    let unit_seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => bail!("Invalid duration. A number followed by s, m, h, d or w expected."),
+    };
+    amount
+        .checked_mul(unit_seconds)
+        .and_then(|seconds| Duration::from_std(std::time::Duration::from_secs(seconds as u64)).ok())
+        .ok_or_else(|| format_err!("Invalid duration. The duration is too long."))
+}
+
 pub trait ArgExt: Sized {
     fn org_arg(self) -> Self;
     fn project_arg(self, multiple: bool) -> Self;
     fn release_arg(self) -> Self;
     fn version_arg(self) -> Self;
+    fn spool_dir_arg(self) -> Self;
 }
 
 impl<'a: 'b, 'b> ArgExt for Command<'a> {
@@ -149,4 +180,25 @@ impl<'a: 'b, 'b> ArgExt for Command<'a> {
                 .help("The version of the release"),
         )
     }
+
+    fn spool_dir_arg(self) -> Command<'a> {
+        self.arg(
+            Arg::new("spool_dir")
+                .value_name("PATH")
+                .long("spool-dir")
+                .help(
+                    "Write envelopes that could not be sent to this directory. \
+                     Defaults to SENTRY_SPOOL_DIR or the spool.dir config value.",
+                ),
+        )
+    }
+}
+
+#[test]
+fn test_get_duration() {
+    assert_eq!(get_duration("90d").unwrap(), Duration::days(90));
+    assert_eq!(get_duration("2w").unwrap(), Duration::weeks(2));
+    assert!(get_duration("90").is_err());
+    assert!(get_duration("-1d").is_err());
+    assert!(get_duration("9999999999999999d").is_err());
 }
@@ -0,0 +1,233 @@
+//! Paths of debug information files in the directory layouts of symbol servers.
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+use symbolic::debuginfo::{FileFormat, Object, ObjectKind};
+
+/// A directory layout understood by debuggers and symbol servers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DifLayout {
+    /// Microsoft SymStore, also used for ELF and Mach-O files by SSQP.
+    SymStore,
+    /// The build ID layout of `debuginfod` servers.
+    Debuginfod,
+    /// The layout of Breakpad symbol servers.
+    Breakpad,
+    /// The unified symbol server layout of Sentry.
+    Unified,
+}
+
+impl DifLayout {
+    pub const NAMES: &'static [&'static str] = &["symstore", "debuginfod", "breakpad", "unified"];
+
+    pub fn all() -> [DifLayout; 4] {
+        [
+            DifLayout::SymStore,
+            DifLayout::Debuginfod,
+            DifLayout::Breakpad,
+            DifLayout::Unified,
+        ]
+    }
+}
+
+impl FromStr for DifLayout {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<DifLayout> {
+        Ok(match s {
+            "symstore" => DifLayout::SymStore,
+            "debuginfod" => DifLayout::Debuginfod,
+            "breakpad" => DifLayout::Breakpad,
+            "unified" => DifLayout::Unified,
+            _ => bail!("Unknown symbol server layout '{}'", s),
+        })
+    }
+}
+
+impl fmt::Display for DifLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DifLayout::SymStore => "symstore",
+            DifLayout::Debuginfod => "debuginfod",
+            DifLayout::Breakpad => "breakpad",
+            DifLayout::Unified => "unified",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn is_executable(object: &Object<'_>) -> bool {
+    matches!(
+        object.kind(),
+        ObjectKind::Executable | ObjectKind::Library | ObjectKind::Relocatable
+    )
+}
+
+/// Returns the path of a Breakpad symbol file in the Breakpad and SymStore
+/// layouts, which both use `<name>/<id>/<name>.sym`.
+fn breakpad_path(object: &Object<'_>) -> Option<String> {
+    let name = match object {
+        Object::Breakpad(ref breakpad) => breakpad.name(),
+        _ => return None,
+    };
+    // Module names of PDBs may be Windows paths.
+    let name = name.rsplit(&['/', '\\'][..]).next().unwrap_or(name);
+    let stem = name.strip_suffix(".pdb").unwrap_or(name);
+    Some(format!(
+        "{}/{}/{}.sym",
+        name,
+        object.debug_id().breakpad(),
+        stem
+    ))
+}
+
+fn symstore_paths(object: &Object<'_>, name: &str) -> Vec<String> {
+    let code_id = object.code_id();
+    let uuid = object.debug_id().uuid().as_simple().to_string();
+    let mut rv = vec![];
+    match object.file_format() {
+        FileFormat::Pe => {
+            if let Some(code_id) = code_id {
+                let id = code_id.as_str().to_uppercase();
+                rv.push(format!("{}/{}/{}", name, id, name));
+            }
+        }
+        FileFormat::Pdb => {
+            let id = object.debug_id().breakpad().to_string().to_uppercase();
+            rv.push(format!("{}/{}/{}", name, id, name));
+        }
+        FileFormat::Elf => {
+            if let Some(code_id) = code_id {
+                if is_executable(object) {
+                    rv.push(format!("{}/elf-buildid-{}/{}", name, code_id, name));
+                }
+                if object.has_debug_info() {
+                    rv.push(format!("_.debug/elf-buildid-sym-{}/_.debug", code_id));
+                }
+            }
+        }
+        FileFormat::MachO => {
+            if is_executable(object) {
+                rv.push(format!("{}/mach-uuid-{}/{}", name, uuid, name));
+            }
+            if object.has_debug_info() {
+                rv.push(format!("_.dwarf/mach-uuid-sym-{}/_.dwarf", uuid));
+            }
+        }
+        FileFormat::Breakpad => rv.extend(breakpad_path(object)),
+        _ => {}
+    }
+    rv
+}
+
+fn debuginfod_paths(object: &Object<'_>) -> Vec<String> {
+    let code_id = match object.code_id() {
+        Some(code_id) if object.file_format() == FileFormat::Elf => code_id,
+        _ => return vec![],
+    };
+    let mut rv = vec![];
+    if is_executable(object) {
+        rv.push(format!("buildid/{}/executable", code_id));
+    }
+    if object.has_debug_info() {
+        rv.push(format!("buildid/{}/debuginfo", code_id));
+    }
+    rv
+}
+
+fn unified_paths(object: &Object<'_>) -> Vec<String> {
+    // Build IDs identify ELF and Mach-O files, all other formats use their
+    // debug identifier.
+    let id = match (object.file_format(), object.code_id()) {
+        (FileFormat::Elf, Some(code_id))
+        | (FileFormat::MachO, Some(code_id))
+        | (FileFormat::Wasm, Some(code_id)) => code_id.to_string(),
+        _ => object.debug_id().breakpad().to_string().to_lowercase(),
+    };
+    if id.len() < 3 {
+        return vec![];
+    }
+
+    let kinds = match object.file_format() {
+        FileFormat::Breakpad => vec!["breakpad"],
+        FileFormat::SourceBundle => vec!["sourcebundle"],
+        _ => {
+            let mut kinds = vec![];
+            if is_executable(object) {
+                kinds.push("executable");
+            }
+            if object.has_debug_info() {
+                kinds.push("debuginfo");
+            }
+            kinds
+        }
+    };
+    kinds
+        .into_iter()
+        .map(|kind| format!("{}/{}/{}", &id[..2], &id[2..], kind))
+        .collect()
+}
+
+/// Returns the paths of an object relative to the root of a layout.
+///
+/// `file_name` is the name of the file containing the object, which some
+/// layouts use as part of the path. Objects that a layout cannot store
+/// return no paths.
+pub fn layout_paths(layout: DifLayout, object: &Object<'_>, file_name: &str) -> Vec<String> {
+    let name = Path::new(file_name)
+        .file_name()
+        .map_or_else(|| file_name.into(), |name| name.to_string_lossy());
+    match layout {
+        DifLayout::SymStore => symstore_paths(object, &name),
+        DifLayout::Debuginfod => debuginfod_paths(object),
+        DifLayout::Breakpad => breakpad_path(object).into_iter().collect(),
+        DifLayout::Unified => unified_paths(object),
+    }
+}
+
+#[test]
+fn test_layout_paths_elf() {
+    let data = std::fs::read("tests/integration/_fixtures/elf-Linux-ARMv7-ls").unwrap();
+    let object = Object::parse(&data).unwrap();
+    let name = "bin/elf-Linux-ARMv7-ls";
+    let build_id = "02547a308094c28e25f1a4adc744a9917194db0a";
+
+    assert_eq!(
+        layout_paths(DifLayout::SymStore, &object, name),
+        [format!(
+            "elf-Linux-ARMv7-ls/elf-buildid-{}/elf-Linux-ARMv7-ls",
+            build_id
+        )]
+    );
+    assert_eq!(
+        layout_paths(DifLayout::Debuginfod, &object, name),
+        [format!("buildid/{}/executable", build_id)]
+    );
+    assert!(layout_paths(DifLayout::Breakpad, &object, name).is_empty());
+    assert_eq!(
+        layout_paths(DifLayout::Unified, &object, name),
+        [format!("02/{}/executable", &build_id[2..])]
+    );
+}
+
+#[test]
+fn test_layout_paths_breakpad() {
+    let data = b"MODULE windows x86_64 3249D99D0C4049318610F4E4FB0B69361 C:\\build\\app.pdb\n";
+    let object = Object::parse(data).unwrap();
+    let path = "app.pdb/3249D99D0C4049318610F4E4FB0B69361/app.sym";
+
+    assert_eq!(
+        layout_paths(DifLayout::Breakpad, &object, "app.sym"),
+        [path]
+    );
+    assert_eq!(
+        layout_paths(DifLayout::SymStore, &object, "app.sym"),
+        [path]
+    );
+    assert_eq!(
+        layout_paths(DifLayout::Unified, &object, "app.sym"),
+        ["32/49d99d0c4049318610f4e4fb0b69361/breakpad"]
+    );
+}
@@ -0,0 +1,194 @@
+//! A manifest that records the progress of debug information file uploads.
+//!
+//! Hashing large debug files dominates the time of an upload. The manifest
+//! stores the checksums of all files found in the file system along with their
+//! size and modification time, so that an interrupted upload can be resumed
+//! without hashing unchanged files again.
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use sha1_smol::Digest;
+use symbolic::common::DebugId;
+
+/// How far the upload of a file has progressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ManifestState {
+    /// The checksums of the file are known, but nothing was uploaded yet.
+    Hashed,
+    /// All chunks of the file were uploaded, but it may not be assembled.
+    Uploaded,
+    /// The server has assembled the file.
+    Assembled,
+}
+
+/// The size and modification time of a file, used to detect changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub size: u64,
+    pub modified: DateTime<Utc>,
+}
+
+impl FileStamp {
+    /// Reads the stamp of the file at the given path.
+    pub fn of(path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(path)?;
+        Ok(FileStamp {
+            size: metadata.len(),
+            modified: metadata.modified()?.into(),
+        })
+    }
+}
+
+/// A debug information file recorded in the manifest.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    /// The file the debug information file was read from.
+    pub path: PathBuf,
+    pub debug_id: Option<DebugId>,
+    #[serde(flatten)]
+    pub stamp: FileStamp,
+    pub checksum: Digest,
+    pub chunks: Vec<Digest>,
+    pub state: ManifestState,
+}
+
+/// The uploads of a project recorded in a JSON file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadManifest {
+    #[serde(skip)]
+    path: PathBuf,
+    org: String,
+    project: String,
+    chunk_size: u64,
+    files: Vec<ManifestEntry>,
+}
+
+impl UploadManifest {
+    /// Loads the manifest at the given path.
+    ///
+    /// If the file does not exist or was recorded for a different project or
+    /// chunk size, an empty manifest is returned.
+    pub fn load(path: &Path, org: &str, project: &str, chunk_size: u64) -> Result<Self> {
+        let empty = UploadManifest {
+            path: path.to_path_buf(),
+            org: org.to_string(),
+            project: project.to_string(),
+            chunk_size,
+            files: vec![],
+        };
+
+        let contents = match fs::read(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(empty),
+            Err(err) => return Err(err.into()),
+        };
+        let mut manifest: UploadManifest = serde_json::from_slice(&contents)
+            .with_context(|| format!("Invalid upload manifest {}", path.display()))?;
+
+        if manifest.org != org || manifest.project != project || manifest.chunk_size != chunk_size {
+            warn!(
+                "Ignoring upload manifest {} recorded for {}/{}",
+                path.display(),
+                manifest.org,
+                manifest.project
+            );
+            return Ok(empty);
+        }
+
+        manifest.path = path.to_path_buf();
+        Ok(manifest)
+    }
+
+    /// Returns the entry of a file if it has not changed since it was recorded.
+    pub fn get(
+        &self,
+        path: &Path,
+        debug_id: Option<DebugId>,
+        stamp: FileStamp,
+    ) -> Option<&ManifestEntry> {
+        self.files
+            .iter()
+            .find(|entry| entry.path == path && entry.debug_id == debug_id && entry.stamp == stamp)
+    }
+
+    /// Records a file, replacing previous records of the same file.
+    pub fn insert(&mut self, entry: ManifestEntry) {
+        self.files
+            .retain(|other| other.path != entry.path || other.debug_id != entry.debug_id);
+        self.files.push(entry);
+    }
+
+    /// Returns whether the server has assembled a file with this checksum.
+    pub fn is_assembled(&self, checksum: Digest) -> bool {
+        self.files
+            .iter()
+            .any(|entry| entry.checksum == checksum && entry.state == ManifestState::Assembled)
+    }
+
+    /// Updates the state of all files with the given checksums.
+    pub fn set_state<I>(&mut self, checksums: I, state: ManifestState)
+    where
+        I: IntoIterator<Item = Digest>,
+    {
+        let checksums: BTreeSet<_> = checksums.into_iter().collect();
+        for entry in &mut self.files {
+            if checksums.contains(&entry.checksum) {
+                entry.state = state;
+            }
+        }
+    }
+
+    /// Writes the manifest back to its file.
+    ///
+    /// The manifest is written to a temporary file first, so that it stays
+    /// intact if the process is killed while saving.
+    pub fn save(&self) -> Result<()> {
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+        let contents = serde_json::to_vec_pretty(self)?;
+        fs::write(&temp_path, contents)
+            .and_then(|_| fs::rename(&temp_path, &self.path))
+            .with_context(|| format!("Could not write upload manifest {}", self.path.display()))
+    }
+}
+
+#[test]
+fn test_manifest_roundtrip() {
+    let dir = crate::utils::fs::TempDir::create().unwrap();
+    let path = dir.path().join("manifest.json");
+    let stamp = FileStamp {
+        size: 42,
+        modified: "2022-06-21T10:00:00Z".parse().unwrap(),
+    };
+    let checksum: Digest = "1d4ba6e2d5c82d5e2c29e0ac4e94e12b97f5d0a8".parse().unwrap();
+
+    let mut manifest = UploadManifest::load(&path, "org", "project", 8).unwrap();
+    manifest.insert(ManifestEntry {
+        path: "app.sym".into(),
+        debug_id: None,
+        stamp,
+        checksum,
+        chunks: vec![checksum],
+        state: ManifestState::Hashed,
+    });
+    manifest.set_state([checksum], ManifestState::Assembled);
+    manifest.save().unwrap();
+
+    let manifest = UploadManifest::load(&path, "org", "project", 8).unwrap();
+    assert!(manifest.is_assembled(checksum));
+    assert!(manifest.get(Path::new("app.sym"), None, stamp).is_some());
+    let changed = FileStamp { size: 43, ..stamp };
+    assert!(manifest.get(Path::new("app.sym"), None, changed).is_none());
+
+    let other = UploadManifest::load(&path, "org", "other", 8).unwrap();
+    assert!(!other.is_assembled(checksum));
+}
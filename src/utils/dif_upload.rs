@@ -21,6 +21,7 @@ use anyhow::{bail, format_err, Error, Result};
 use console::style;
 use indicatif::HumanBytes;
 use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
 use sha1_smol::Digest;
 use symbolic::common::{AsSelf, ByteView, DebugId, SelfCell, Uuid};
 use symbolic::debuginfo::macho::{BcSymbolMap, UuidMapping};
@@ -41,6 +42,8 @@ use crate::utils::chunks::{
     upload_chunks, BatchedSliceExt, Chunk, ItemSize, ASSEMBLE_POLL_INTERVAL,
 };
 use crate::utils::dif::ObjectDifFeatures;
+use crate::utils::dif_layout::{layout_paths, DifLayout};
+use crate::utils::dif_manifest::{FileStamp, ManifestEntry, ManifestState, UploadManifest};
 use crate::utils::fs::{get_sha1_checksum, get_sha1_checksums, TempDir, TempFile};
 use crate::utils::progress::{ProgressBar, ProgressStyle};
 use crate::utils::ui::{copy_with_progress, make_byte_progress_bar};
@@ -111,6 +114,8 @@ struct DifMatch<'data> {
     name: String,
     debug_id: Option<DebugId>,
     attachments: Option<BTreeMap<String, ByteView<'static>>>,
+    /// The file this DIF was read from, if it was found in the file system.
+    file_path: Option<PathBuf>,
 }
 
 impl<'data> DifMatch<'data> {
@@ -132,6 +137,7 @@ impl<'data> DifMatch<'data> {
             name: name.into(),
             debug_id: None,
             attachments: None,
+            file_path: None,
         })
     }
 
@@ -152,6 +158,7 @@ impl<'data> DifMatch<'data> {
             name: name.into(),
             debug_id,
             attachments: None,
+            file_path: None,
         })
     }
     /// Creates a [`DifMatch`] from a `.bcsymbolmap` file.
@@ -169,6 +176,7 @@ impl<'data> DifMatch<'data> {
             name,
             debug_id: Some(uuid),
             attachments: None,
+            file_path: None,
         })
     }
 
@@ -183,6 +191,7 @@ impl<'data> DifMatch<'data> {
             name,
             debug_id: Some(uuid),
             attachments: None,
+            file_path: None,
         })
     }
 
@@ -339,6 +348,42 @@ impl<'data> ChunkedDifMatch<'data> {
         })
     }
 
+    /// Like [`ChunkedDifMatch::from`], but reuses the checksums recorded in
+    /// the manifest if the file has not changed since.
+    pub fn from_manifest(
+        inner: DifMatch<'data>,
+        chunk_size: u64,
+        manifest: &mut UploadManifest,
+    ) -> Result<Self> {
+        let path = match inner.file_path {
+            Some(ref path) => path.clone(),
+            None => return Self::from(inner, chunk_size),
+        };
+        let stamp = FileStamp::of(&path)?;
+        if let Some(entry) = manifest.get(&path, inner.debug_id, stamp) {
+            debug!("reusing checksums of {}", path.display());
+            return Ok(ChunkedDifMatch {
+                inner: HashedDifMatch {
+                    inner,
+                    checksum: entry.checksum,
+                },
+                chunks: entry.chunks.clone(),
+                chunk_size,
+            });
+        }
+
+        let chunked = Self::from(inner, chunk_size)?;
+        manifest.insert(ManifestEntry {
+            path,
+            debug_id: chunked.debug_id,
+            stamp,
+            checksum: chunked.checksum(),
+            chunks: chunked.chunks.clone(),
+            state: ManifestState::Hashed,
+        });
+        Ok(chunked)
+    }
+
     /// Returns an iterator over all chunk checksums.
     pub fn checksums(&self) -> Iter<'_, Digest> {
         self.chunks.iter()
@@ -556,6 +601,11 @@ where
         }
 
         let path = entry.path();
+        if options.is_excluded(path) {
+            debug!("skipping previously exported {}", path.display());
+            continue;
+        }
+
         if options.zips_allowed {
             match try_open_zip(path) {
                 Ok(Some(zip)) => {
@@ -838,6 +888,10 @@ fn collect_object_dif<'a>(
     // which needs to retain a reference to the original fat file. We
     // create a shared instance here and clone it into `DifMatche`s
     // below.
+    let file_path = match source {
+        DifSource::FileSystem(path) => Some(path.to_path_buf()),
+        _ => None,
+    };
     for object in archive.objects() {
         // Silently skip all objects that we cannot process. This can
         // happen due to invalid object files, which we then just
@@ -887,6 +941,7 @@ fn collect_object_dif<'a>(
             name: name.clone(),
             debug_id: Some(id),
             attachments,
+            file_path: file_path.clone(),
         };
 
         // Skip this file if we don't want to process it.
@@ -1197,11 +1252,12 @@ fn create_il2cpp_mappings<'a>(difs: &[DifMatch<'a>]) -> Result<Vec<DifMatch<'a>>
 /// with info on missing chunks.
 ///
 /// The returned value contains separate vectors for incomplete DIFs and
-/// missing chunks for convenience.
+/// missing chunks for convenience, as well as the checksums of all DIFs that
+/// the server has already assembled successfully.
 fn try_assemble_difs<'data, 'm>(
     difs: &'m [ChunkedDifMatch<'data>],
     options: &DifUpload,
-) -> Result<MissingDifsInfo<'data, 'm>> {
+) -> Result<(MissingDifsInfo<'data, 'm>, Vec<Digest>)> {
     let api = Api::current();
     let request = difs
         .iter()
@@ -1222,6 +1278,7 @@ fn try_assemble_difs<'data, 'm>(
 
     let mut difs = Vec::new();
     let mut chunks = Vec::new();
+    let mut assembled = Vec::new();
     for (checksum, ref file_response) in response {
         let chunked_match = *difs_by_checksum
             .get(&checksum)
@@ -1261,13 +1318,18 @@ fn try_assemble_difs<'data, 'm>(
 
                 chunks.extend(missing_chunks);
             }
-            _ => {
+            ChunkedFileState::Ok => {
                 // This file has already finished. No action required anymore.
+                assembled.push(checksum);
+            }
+            ChunkedFileState::Created => {
+                // The server has received all chunks but has not started
+                // assembling yet. There is nothing to upload.
             }
         }
     }
 
-    Ok((difs, chunks))
+    Ok(((difs, chunks), assembled))
 }
 
 /// Concurrently uploads chunks specified in `missing_info` in batches. The
@@ -1523,19 +1585,69 @@ fn upload_difs_chunked(
         processed.extend(source_bundles);
     }
 
-    // Calculate checksums and chunks
-    let chunked = prepare_difs(processed, |m| {
-        ChunkedDifMatch::from(m, chunk_options.chunk_size)
+    let mut manifest = match options.manifest {
+        Some(ref path) => Some(UploadManifest::load(
+            path,
+            &options.org,
+            &options.project,
+            chunk_options.chunk_size,
+        )?),
+        None => None,
+    };
+
+    // Calculate checksums and chunks, unless the manifest already knows them
+    let mut chunked = prepare_difs(processed, |m| match manifest {
+        Some(ref mut manifest) => {
+            ChunkedDifMatch::from_manifest(m, chunk_options.chunk_size, manifest)
+        }
+        None => ChunkedDifMatch::from(m, chunk_options.chunk_size),
     })?;
 
+    if let Some(ref manifest) = manifest {
+        manifest.save()?;
+        let total = chunked.len();
+        chunked.retain(|m| !manifest.is_assembled(m.checksum()));
+        if chunked.len() < total {
+            println!(
+                "{} Skipping {} debug information files uploaded by a previous run",
+                style(">").dim(),
+                style(total - chunked.len()).yellow()
+            );
+        }
+    }
+
     // Upload missing chunks to the server and remember incomplete difs
-    let missing_info = try_assemble_difs(&chunked, options)?;
+    let (missing_info, assembled) = if chunked.is_empty() {
+        Default::default()
+    } else {
+        try_assemble_difs(&chunked, options)?
+    };
+    if let Some(ref mut manifest) = manifest {
+        manifest.set_state(assembled, ManifestState::Assembled);
+        manifest.save()?;
+    }
+
     upload_missing_chunks(&missing_info, chunk_options)?;
+    if let Some(ref mut manifest) = manifest {
+        manifest.set_state(
+            missing_info.0.iter().map(|m| m.checksum()),
+            ManifestState::Uploaded,
+        );
+        manifest.save()?;
+    }
 
     // Only if DIFs were missing, poll until assembling is complete
     let (missing_difs, _) = missing_info;
     if !missing_difs.is_empty() {
-        poll_dif_assemble(&missing_difs, options)
+        let (uploaded, has_errors) = poll_dif_assemble(&missing_difs, options)?;
+        if let Some(ref mut manifest) = manifest {
+            manifest.set_state(
+                uploaded.iter().filter_map(|dif| dif.checksum.parse().ok()),
+                ManifestState::Assembled,
+            );
+            manifest.save()?;
+        }
+        Ok((uploaded, has_errors))
     } else {
         println!(
             "{} Nothing to upload, all files are on the server",
@@ -1660,6 +1772,130 @@ fn upload_difs_batched(options: &DifUpload) -> Result<Vec<DebugInfoFile>> {
     Ok(uploaded)
 }
 
+/// A debug information file copied into a symbol server layout.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedDif {
+    /// The path relative to the root of the layout.
+    pub path: String,
+    #[serde(rename = "debugId")]
+    pub debug_id: DebugId,
+    #[serde(rename = "codeId", default, skip_serializing_if = "Option::is_none")]
+    pub code_id: Option<String>,
+    pub arch: String,
+    #[serde(rename = "type")]
+    pub file_format: String,
+    pub size: u64,
+}
+
+/// The index of all files in an exported symbol server directory.
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportIndex {
+    layout: String,
+    files: Vec<ExportedDif>,
+}
+
+/// The name of the index file in the root of an exported directory.
+const EXPORT_INDEX: &str = "index.json";
+
+/// Writes a DIF to the target path, linking the original file if possible.
+///
+/// The file is written next to the target first and then moved into place, so
+/// that an existing target is only replaced once the new file is complete.
+fn export_dif(dif: &DifMatch<'_>, target: &Path, hardlink: bool) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if let (Some(source), Ok(target)) = (&dif.file_path, target.canonicalize()) {
+        if source.canonicalize()? == target {
+            debug!("{} is already exported", target.display());
+            return Ok(());
+        }
+    }
+
+    let mut temp_path = target.to_path_buf().into_os_string();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    if temp_path.exists() {
+        fs::remove_file(&temp_path)?;
+    }
+
+    let mut linked = false;
+    if let (true, Some(source)) = (hardlink, &dif.file_path) {
+        // Only files that contain nothing but this DIF can be linked.
+        if fs::metadata(source)?.len() == dif.size() {
+            match fs::hard_link(source, &temp_path) {
+                Ok(()) => linked = true,
+                // Links fail across file systems, fall back to copying.
+                Err(err) => debug!("could not link {}: {}", source.display(), err),
+            }
+        }
+    }
+    if !linked {
+        fs::write(&temp_path, dif.data())?;
+    }
+    fs::rename(&temp_path, target)?;
+    // Renaming does nothing if both paths are links to the same file.
+    if temp_path.exists() {
+        fs::remove_file(&temp_path)?;
+    }
+    Ok(())
+}
+
+/// Returns the canonical paths of all files listed in the index of an
+/// exported directory.
+fn read_exported_files(destination: &Path) -> BTreeSet<PathBuf> {
+    let index: ExportIndex = match fs::read(destination.join(EXPORT_INDEX))
+        .ok()
+        .and_then(|contents| serde_json::from_slice(&contents).ok())
+    {
+        Some(index) => index,
+        None => return BTreeSet::new(),
+    };
+    index
+        .files
+        .iter()
+        .filter_map(|dif| destination.join(&dif.path).canonicalize().ok())
+        .collect()
+}
+
+/// Adds the exported files to the index of the destination directory.
+fn update_export_index(
+    destination: &Path,
+    layout: DifLayout,
+    exported: Vec<ExportedDif>,
+) -> Result<()> {
+    let index_path = destination.join(EXPORT_INDEX);
+    let mut files = match fs::read(&index_path) {
+        Ok(contents) => {
+            let index: ExportIndex = serde_json::from_slice(&contents)?;
+            if index.layout != layout.to_string() {
+                bail!(
+                    "{} already contains symbols in the {} layout",
+                    destination.display(),
+                    index.layout
+                );
+            }
+            index.files
+        }
+        Err(_) => vec![],
+    };
+
+    let paths: BTreeSet<_> = exported.iter().map(|dif| dif.path.clone()).collect();
+    files.retain(|dif| !paths.contains(&dif.path));
+    files.extend(exported);
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let index = ExportIndex {
+        layout: layout.to_string(),
+        files,
+    };
+    let mut file = BufWriter::new(File::create(index_path)?);
+    serde_json::to_writer_pretty(&mut file, &index)?;
+    file.write_all(b"\n")?;
+    Ok(())
+}
+
 /// The format of a Debug Information File (DIF).
 ///
 /// Most DIFs are also object files, but we also know of some auxiliary DIF formats.
@@ -1730,6 +1966,8 @@ pub struct DifUpload {
     wait: bool,
     upload_il2cpp_mappings: bool,
     il2cpp_mappings_allowed: bool,
+    manifest: Option<PathBuf>,
+    excluded_files: BTreeSet<PathBuf>,
 }
 
 impl DifUpload {
@@ -1769,6 +2007,8 @@ impl DifUpload {
             wait: false,
             upload_il2cpp_mappings: false,
             il2cpp_mappings_allowed: false,
+            manifest: None,
+            excluded_files: BTreeSet::new(),
         }
     }
 
@@ -1920,6 +2160,19 @@ impl DifUpload {
         self
     }
 
+    /// Set a manifest file that records the progress of the upload.
+    ///
+    /// When an upload is interrupted, running it again with the same manifest
+    /// skips hashing unchanged files and files that were already assembled.
+    /// The manifest is only used by servers that support chunked uploads.
+    pub fn manifest<P>(&mut self, path: P) -> &mut Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.manifest = Some(path.into());
+        self
+    }
+
     /// Performs the search for DIFs and uploads them.
     ///
     /// ```
@@ -1962,6 +2215,65 @@ impl DifUpload {
         Ok((upload_difs_batched(self)?, false))
     }
 
+    /// Performs the search for DIFs and copies them into a symbol server
+    /// directory layout instead of uploading them.
+    ///
+    /// With `hardlink`, files that contain exactly one DIF are linked rather
+    /// than copied. All exported files are added to an `index.json` in the
+    /// destination directory.
+    pub fn export(
+        &mut self,
+        destination: &Path,
+        layout: DifLayout,
+        hardlink: bool,
+    ) -> Result<Vec<String>> {
+        if self.paths.is_empty() {
+            println!("{}: No paths were provided.", style("Warning").yellow());
+            return Ok(Default::default());
+        }
+
+        // Without a server, all object formats and sizes can be exported.
+        self.pdbs_allowed = true;
+        self.sources_allowed = true;
+        self.max_file_size = u64::MAX;
+
+        // Never pick up previously exported files from the destination, which
+        // may be nested in one of the search paths.
+        self.excluded_files = read_exported_files(destination);
+        let difs = search_difs(self)?;
+        let mut exported = vec![];
+        for dif in &difs {
+            let object = match dif.object() {
+                Some(object) => object,
+                None => continue,
+            };
+            let paths = layout_paths(layout, object, dif.path());
+            if paths.is_empty() {
+                warn!(
+                    "Skipping {} which cannot be stored in the {} layout",
+                    dif.path(),
+                    layout
+                );
+                continue;
+            }
+            for path in paths {
+                export_dif(dif, &destination.join(&path), hardlink)?;
+                exported.push(ExportedDif {
+                    path,
+                    debug_id: dif.debug_id.unwrap_or_else(|| object.debug_id()),
+                    code_id: object.code_id().map(|code_id| code_id.to_string()),
+                    arch: object.arch().name().to_string(),
+                    file_format: object.file_format().to_string(),
+                    size: dif.size(),
+                });
+            }
+        }
+
+        let paths = exported.iter().map(|dif| dif.path.clone()).collect();
+        update_export_index(destination, layout, exported)?;
+        Ok(paths)
+    }
+
     /// Validate that the server supports all requested capabilities.
     fn validate_capabilities(&mut self) {
         // Checks whether source bundles are *explicitly* requested on the command line.
@@ -2003,6 +2315,17 @@ impl DifUpload {
     }
 
     /// Determines if this file extension matches the search criteria.
+    /// Returns whether a file is excluded from the search.
+    fn is_excluded(&self, path: &Path) -> bool {
+        if self.excluded_files.is_empty() {
+            return false;
+        }
+        match path.canonicalize() {
+            Ok(path) => self.excluded_files.contains(&path),
+            Err(_) => false,
+        }
+    }
+
     fn valid_extension(&self, ext: Option<&OsStr>) -> bool {
         self.extensions.is_empty() || ext.map_or(false, |e| self.extensions.contains(e))
     }
@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::fs;
 use std::io::{BufRead, BufReader};
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
@@ -9,7 +10,7 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use sentry::protocol::{Breadcrumb, ClientSdkInfo, Event};
 use sentry::types::Dsn;
-use sentry::{apply_defaults, Client, ClientOptions};
+use sentry::{apply_defaults, Client, ClientOptions, Envelope, TransportFactory};
 
 use crate::constants::USER_AGENT;
 
@@ -75,6 +76,25 @@ pub fn get_sdk_info() -> Cow<'static, ClientSdkInfo> {
 /// after the callback has finished and drain its queue with a timeout of 2 seconds. The return
 /// value of the callback is passed through to the caller.
 pub fn with_sentry_client<F, R>(dsn: Dsn, callback: F) -> R
+where
+    F: FnOnce(&Client) -> R,
+{
+    with_client_options(dsn, ClientOptions::default(), callback)
+}
+
+/// Like `with_sentry_client`, but sends envelopes through a custom transport.
+pub fn with_sentry_transport<F, R>(dsn: Dsn, transport: Arc<dyn TransportFactory>, callback: F) -> R
+where
+    F: FnOnce(&Client) -> R,
+{
+    let options = ClientOptions {
+        transport: Some(transport),
+        ..Default::default()
+    };
+    with_client_options(dsn, options, callback)
+}
+
+fn with_client_options<F, R>(dsn: Dsn, options: ClientOptions, callback: F) -> R
 where
     F: FnOnce(&Client) -> R,
 {
@@ -82,7 +102,7 @@ where
         dsn,
         apply_defaults(ClientOptions {
             user_agent: USER_AGENT.into(),
-            ..Default::default()
+            ..options
         }),
     ));
 
@@ -90,3 +110,12 @@ where
     client.close(Some(Duration::from_secs(2)));
     rv
 }
+
+/// Serializes an envelope into its wire format.
+pub fn serialize_envelope(envelope: &Envelope) -> Result<Vec<u8>> {
+    let mut rv = vec![];
+    envelope
+        .to_writer(&mut rv)
+        .context("Could not serialize envelope")?;
+    Ok(rv)
+}
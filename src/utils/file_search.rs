@@ -10,6 +10,7 @@ use ignore::types::TypesBuilder;
 use ignore::WalkBuilder;
 use log::{info, warn};
 
+use crate::utils::logging::is_quiet_mode;
 use crate::utils::progress::{ProgressBar, ProgressStyle};
 
 use super::fs::{decompress_gzip_content, is_gzip_compressed};
@@ -185,15 +186,17 @@ impl ReleaseFileSearch {
         }
 
         pb.finish_and_clear();
-        println!(
-            "{} Found {} release {}",
-            style(">").dim(),
-            style(collected.len()).yellow(),
-            match collected.len() {
-                1 => "file",
-                _ => "files",
-            }
-        );
+        if !is_quiet_mode() {
+            println!(
+                "{} Found {} release {}",
+                style(">").dim(),
+                style(collected.len()).yellow(),
+                match collected.len() {
+                    1 => "file",
+                    _ => "files",
+                }
+            );
+        }
 
         Ok(collected)
     }
@@ -1,8 +1,9 @@
 //! Searches, processes and uploads release files.
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::fs::File;
 use std::io::BufWriter;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -12,16 +13,21 @@ use console::style;
 use parking_lot::RwLock;
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
+use serde::{Deserialize, Serialize};
 use sha1_smol::Digest;
 use symbolic::common::ByteView;
-use symbolic::debuginfo::sourcebundle::{SourceBundleWriter, SourceFileInfo, SourceFileType};
+use symbolic::debuginfo::sourcebundle::{
+    SourceBundle, SourceBundleWriter, SourceFileInfo, SourceFileType,
+};
 use url::Url;
+use zip::ZipArchive;
 
 use crate::api::{Api, ChunkUploadCapability, ChunkUploadOptions, ProgressBarMode};
 use crate::constants::DEFAULT_MAX_WAIT;
 use crate::utils::chunks::{upload_chunks, Chunk, ASSEMBLE_POLL_INTERVAL};
 use crate::utils::fs::{get_sha1_checksum, get_sha1_checksums, TempFile};
 use crate::utils::progress::{ProgressBar, ProgressStyle};
+use crate::utils::sourcemaps::get_debug_id;
 
 /// Fallback concurrency for release file uploads.
 static DEFAULT_CONCURRENCY: usize = 4;
@@ -34,7 +40,8 @@ pub struct UploadContext<'a> {
     pub wait: bool,
 }
 
-#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Debug, Copy, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum LogLevel {
     Warning,
     Error,
@@ -210,7 +217,15 @@ fn upload_files_chunked(
     options: &ChunkUploadOptions,
 ) -> Result<()> {
     let archive = build_artifact_bundle(context, files)?;
+    upload_artifact_bundle(context, archive.path(), options)
+}
 
+/// Uploads an artifact bundle in chunks and assembles it on the server.
+pub fn upload_artifact_bundle(
+    context: &UploadContext,
+    path: &Path,
+    options: &ChunkUploadOptions,
+) -> Result<()> {
     let progress_style =
         ProgressStyle::default_spinner().template("{spinner} Optimizing bundle for upload...");
 
@@ -218,7 +233,7 @@ fn upload_files_chunked(
     pb.enable_steady_tick(100);
     pb.set_style(progress_style);
 
-    let view = ByteView::open(archive.path())?;
+    let view = ByteView::open(path)?;
     let (checksum, checksums) = get_sha1_checksums(&view, options.chunk_size)?;
     let chunks = view
         .chunks(options.chunk_size as usize)
@@ -294,6 +309,18 @@ fn upload_files_chunked(
 }
 
 fn build_artifact_bundle(context: &UploadContext, files: &ReleaseFiles) -> Result<TempFile> {
+    let archive = TempFile::create()?;
+    write_artifact_bundle(context, files, archive.open()?)?;
+    Ok(archive)
+}
+
+/// Writes the files into an artifact bundle, along with the upload context as
+/// bundle attributes.
+pub fn write_artifact_bundle(
+    context: &UploadContext,
+    files: &ReleaseFiles,
+    file: File,
+) -> Result<()> {
     let progress_style = ProgressStyle::default_bar().template(
         "{prefix:.dim} Bundling files for upload... {msg:.dim}\
        \n{wide_bar}  {pos}/{len}",
@@ -303,8 +330,7 @@ fn build_artifact_bundle(context: &UploadContext, files: &ReleaseFiles) -> Resul
     pb.set_style(progress_style);
     pb.set_prefix(">");
 
-    let archive = TempFile::create()?;
-    let mut bundle = SourceBundleWriter::start(BufWriter::new(archive.open()?))?;
+    let mut bundle = SourceBundleWriter::start(BufWriter::new(file))?;
 
     bundle.set_attribute("org".to_owned(), context.org.to_owned());
     if let Some(project) = context.project {
@@ -325,6 +351,9 @@ fn build_artifact_bundle(context: &UploadContext, files: &ReleaseFiles) -> Resul
         for (k, v) in &file.headers {
             info.add_header(k.clone(), v.clone());
         }
+        if let Some(debug_id) = get_debug_id(file) {
+            info.add_header("debug-id".to_owned(), debug_id.to_string());
+        }
 
         let bundle_path = url_to_bundle_path(&file.url)?;
         bundle.add_file(bundle_path, file.contents.as_slice(), info)?;
@@ -344,7 +373,35 @@ fn build_artifact_bundle(context: &UploadContext, files: &ReleaseFiles) -> Resul
 
     pb.finish_with_duration("Bundling");
 
-    Ok(archive)
+    Ok(())
+}
+
+/// The part of an artifact bundle manifest that is read back from disk.
+///
+/// Attributes are stored next to the `files` of the manifest.
+#[derive(Deserialize)]
+struct ArtifactBundleManifest {
+    #[serde(flatten)]
+    attributes: BTreeMap<String, serde_json::Value>,
+}
+
+/// Reads the attributes of an artifact bundle, such as `org` and `release`.
+pub fn read_artifact_bundle_attributes(path: &Path) -> Result<BTreeMap<String, String>> {
+    let view = ByteView::open(path)?;
+    if !SourceBundle::test(&view) {
+        bail!("{} is not an artifact bundle", path.display());
+    }
+    let mut archive = ZipArchive::new(std::io::Cursor::new(&*view))?;
+    let manifest: ArtifactBundleManifest =
+        serde_json::from_reader(archive.by_name("manifest.json")?)?;
+    Ok(manifest
+        .attributes
+        .into_iter()
+        .filter_map(|(key, value)| match value {
+            serde_json::Value::String(value) => Some((key, value)),
+            _ => None,
+        })
+        .collect())
 }
 
 fn url_to_bundle_path(url: &str) -> Result<String> {
@@ -0,0 +1,198 @@
+//! Support for React Native Hermes bytecode bundles and their sourcemaps.
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+use sourcemap::SourceMap;
+use symbolic::common::DebugId;
+
+use crate::utils::sourcemap_compose::{compose_sourcemaps, decode_flat_sourcemap};
+
+/// The magic of the hermes bytecode format, defined here:
+/// https://github.com/facebook/hermes/blob/5243222ef1d92b7393d00599fc5cff01d189a88a/include/hermes/BCGen/HBC/BytecodeFileFormat.h#L24-L25
+const HERMES_MAGIC: [u8; 8] = [0xC6, 0x1F, 0xBC, 0x03, 0xC1, 0x03, 0x19, 0x1F];
+
+/// The sourcemap field with the bytecode offsets of all functions per segment.
+const FUNCTION_OFFSETS_FIELD: &str = "x_hermes_function_offsets";
+
+pub fn is_hermes_bytecode(slice: &[u8]) -> bool {
+    slice.starts_with(&HERMES_MAGIC)
+}
+
+/// The leading fields of the `BytecodeFileHeader` of a hermes bytecode bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HermesBytecodeHeader {
+    pub version: u32,
+    /// The SHA-1 hash of the JavaScript bundle the bytecode was compiled from.
+    pub source_hash: [u8; 20],
+    pub file_length: u32,
+    pub function_count: u32,
+}
+
+impl HermesBytecodeHeader {
+    pub fn parse(slice: &[u8]) -> Result<Self> {
+        if !is_hermes_bytecode(slice) {
+            bail!("not a hermes bytecode bundle");
+        }
+        if slice.len() < 44 {
+            bail!("hermes bytecode header is truncated");
+        }
+        let read_u32 =
+            |offset: usize| u32::from_le_bytes(slice[offset..offset + 4].try_into().unwrap());
+        Ok(HermesBytecodeHeader {
+            version: read_u32(8),
+            source_hash: slice[12..32].try_into().unwrap(),
+            file_length: read_u32(32),
+            function_count: read_u32(40),
+        })
+    }
+
+    /// A debug ID that stays the same as long as the bundle is compiled from
+    /// the same JavaScript.
+    pub fn debug_id(&self) -> DebugId {
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&self.source_hash[..16]);
+        DebugId::from_uuid(uuid::Builder::from_sha1_bytes(bytes).into_uuid())
+    }
+}
+
+/// Returns the function offsets of a hermes sourcemap as JSON, if any.
+pub fn get_function_offsets_value(contents: &[u8]) -> Option<Value> {
+    let mut sourcemap: Value = serde_json::from_slice(contents).ok()?;
+    sourcemap.get_mut(FUNCTION_OFFSETS_FIELD).map(Value::take)
+}
+
+/// Adds function offsets to a sourcemap, since the sourcemap crate drops
+/// them when it encodes a sourcemap.
+pub fn inject_function_offsets_into_sourcemap(contents: &[u8], offsets: Value) -> Result<Vec<u8>> {
+    let mut sourcemap: Value = serde_json::from_slice(contents)?;
+    match sourcemap.as_object_mut() {
+        Some(object) => {
+            object.insert(FUNCTION_OFFSETS_FIELD.into(), offsets);
+        }
+        None => bail!("sourcemap is not a JSON object"),
+    }
+    Ok(serde_json::to_vec(&sourcemap)?)
+}
+
+/// Returns the bytecode offsets of all functions of a hermes sourcemap,
+/// keyed by segment, which is the line of the sourcemap.
+pub fn get_function_offsets(contents: &[u8]) -> Option<HashMap<u32, Vec<u32>>> {
+    let offsets = get_function_offsets_value(contents)?;
+    let mut rv = HashMap::new();
+    for (segment, offsets) in offsets.as_object()? {
+        let offsets = offsets
+            .as_array()?
+            .iter()
+            .map(|offset| Some(offset.as_u64()? as u32))
+            .collect::<Option<Vec<_>>>()?;
+        rv.insert(segment.parse().ok()?, offsets);
+    }
+    Some(rv)
+}
+
+/// Returns the start offsets of all functions without any mapping to a source.
+///
+/// A function is covered if a mapping with a source starts between its
+/// offset and the offset of the next function.
+pub fn uncovered_functions(sm: &SourceMap, offsets: &HashMap<u32, Vec<u32>>) -> Vec<(u32, u32)> {
+    let mut mapped: HashMap<u32, Vec<u32>> = HashMap::new();
+    for token in sm.tokens().filter(|token| token.has_source()) {
+        mapped
+            .entry(token.get_dst_line())
+            .or_default()
+            .push(token.get_dst_col());
+    }
+    for columns in mapped.values_mut() {
+        columns.sort_unstable();
+    }
+
+    let mut rv = vec![];
+    for (&segment, functions) in offsets {
+        let columns = mapped.get(&segment).map_or(&[][..], Vec::as_slice);
+        let mut functions = functions.clone();
+        functions.sort_unstable();
+        for (idx, &start) in functions.iter().enumerate() {
+            let end = functions.get(idx + 1).copied().unwrap_or(u32::MAX);
+            let first = columns.partition_point(|&col| col < start);
+            if !matches!(columns.get(first), Some(&col) if col < end) {
+                rv.push((segment, start));
+            }
+        }
+    }
+    rv.sort_unstable();
+    rv
+}
+
+/// Composes the sourcemap emitted by the hermes compiler with the sourcemap
+/// of the JavaScript bundle it compiled, as emitted by the Metro packager.
+///
+/// The metadata that React Native uses for function names and the function
+/// offsets of the bytecode are carried over into the composed sourcemap.
+pub fn compose_hermes_sourcemap(hermes_map: &[u8], packager_map: &[u8]) -> Result<Vec<u8>> {
+    let hermes = decode_flat_sourcemap(hermes_map)?;
+    let packager = decode_flat_sourcemap(packager_map)?;
+    let stages = hermes.sources().map(|source| (source, &packager)).collect();
+    let composed = compose_sourcemaps(&hermes, &stages);
+
+    let mut contents = vec![];
+    composed.to_writer(&mut contents)?;
+    let mut sourcemap: Value = serde_json::from_slice(&contents)?;
+    let packager_json: Value = serde_json::from_slice(packager_map)?;
+
+    // `x_facebook_sources` has an entry per source and must follow the order
+    // of the sources of the composed sourcemap.
+    if let Some(facebook_sources) = packager_json
+        .get("x_facebook_sources")
+        .and_then(Value::as_array)
+    {
+        let entries: Vec<Value> = composed
+            .sources()
+            .map(|source| {
+                packager
+                    .sources()
+                    .position(|s| s == source)
+                    .and_then(|idx| facebook_sources.get(idx))
+                    .cloned()
+                    .unwrap_or(Value::Null)
+            })
+            .collect();
+        sourcemap["x_facebook_sources"] = entries.into();
+    }
+    if let Some(module_paths) = packager_json.get("x_metro_module_paths") {
+        sourcemap["x_metro_module_paths"] = module_paths.clone();
+    }
+    if let Some(offsets) = get_function_offsets_value(hermes_map) {
+        sourcemap[FUNCTION_OFFSETS_FIELD] = offsets;
+    }
+    Ok(serde_json::to_vec(&sourcemap)?)
+}
+
+#[test]
+fn test_parse_header() {
+    let mut bytecode = HERMES_MAGIC.to_vec();
+    bytecode.extend(&84u32.to_le_bytes());
+    bytecode.extend(&[0xab; 20]);
+    bytecode.extend(&4096u32.to_le_bytes());
+    bytecode.extend(&0u32.to_le_bytes());
+    bytecode.extend(&3u32.to_le_bytes());
+
+    let header = HermesBytecodeHeader::parse(&bytecode).unwrap();
+    assert_eq!(header.version, 84);
+    assert_eq!(header.source_hash, [0xab; 20]);
+    assert_eq!(header.file_length, 4096);
+    assert_eq!(header.function_count, 3);
+    assert!(HermesBytecodeHeader::parse(&bytecode[..40]).is_err());
+}
+
+#[test]
+fn test_uncovered_functions() {
+    // Mappings with a source at bytecode offsets 0 and 12 of segment 0.
+    let sm = SourceMap::from_slice(
+        br#"{"version":3,"sources":["a.js"],"names":[],"mappings":"AAAA,YAAC"}"#,
+    )
+    .unwrap();
+    let offsets = vec![(0, vec![0, 10, 20])].into_iter().collect();
+    assert_eq!(uncovered_functions(&sm, &offsets), [(0, 20)]);
+}
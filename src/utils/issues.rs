@@ -0,0 +1,81 @@
+use anyhow::Result;
+use clap::ArgMatches;
+use log::info;
+
+use crate::api::{Api, Issue, IssueChanges, IssueFilter};
+use crate::config::Config;
+use crate::utils::formatting::Table;
+
+/// Prints the given issues as a table.
+pub fn print_issues(issues: &[Issue]) {
+    let mut table = Table::new();
+    table
+        .title_row()
+        .add("Short ID")
+        .add("Status")
+        .add("Level")
+        .add("Events")
+        .add("Users")
+        .add("Last Seen")
+        .add("Title");
+
+    for issue in issues {
+        table
+            .add_row()
+            .add(&issue.short_id)
+            .add(&issue.status)
+            .add(&issue.level)
+            .add(&issue.count)
+            .add(issue.user_count)
+            .add(issue.last_seen)
+            .add(&issue.title);
+    }
+
+    table.print();
+}
+
+/// Applies `changes` to all issues selected on the command line.
+///
+/// If `--dry-run` was passed, the selected issues are only listed together
+/// with their count and nothing is updated.
+pub fn update_selected_issues(matches: &ArgMatches, changes: &IssueChanges) -> Result<()> {
+    let config = Config::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+    let filter = IssueFilter::get_filter_from_matches(matches)?;
+    let api = Api::current();
+
+    info!(
+        "Issuing a command for Organization: {} Project: {}",
+        org, project
+    );
+
+    if matches.is_present("dry_run") {
+        let issues = api.list_issues(&org, &project, &filter, None)?;
+        println!("Dry run: {} issue(s) would be updated.", issues.len());
+        print_issues(&issues);
+        return Ok(());
+    }
+
+    if api.bulk_update_issue(&org, &project, &filter, changes)? {
+        println!("Updated matching issues.");
+        if let Some(status) = changes.new_status.as_ref() {
+            println!("  new status: {}", status);
+        }
+        if let Some(assignee) = changes.assigned_to.as_ref() {
+            if assignee.is_empty() {
+                println!("  unassigned");
+            } else {
+                println!("  assigned to: {}", assignee);
+            }
+        }
+        if let Some(bookmarked) = changes.is_bookmarked {
+            println!("  bookmarked: {}", bookmarked);
+        }
+        if changes.merge == Some(true) {
+            println!("  merged into a single issue");
+        }
+    } else {
+        println!("No changes requested.");
+    }
+    Ok(())
+}
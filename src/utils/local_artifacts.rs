@@ -0,0 +1,234 @@
+//! Reads release artifacts from a local build directory or artifact bundle.
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use symbolic::common::{ByteView, DebugId};
+use symbolic::debuginfo::sourcebundle::SourceBundle;
+use url::Url;
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+use crate::utils::file_upload::read_artifact_bundle_attributes;
+use crate::utils::fs::path_as_url;
+use crate::utils::sourcemaps::{
+    get_debug_id_from_minified, get_debug_id_from_sourcemap, get_sourcemap_reference_from_headers,
+    join_url,
+};
+
+/// A release file as it would be uploaded to Sentry.
+#[derive(Debug)]
+pub struct LocalArtifact {
+    pub url: String,
+    /// Where the file was read from, for display purposes.
+    pub location: String,
+    pub contents: Vec<u8>,
+    pub headers: Vec<(String, String)>,
+    pub debug_id: Option<DebugId>,
+}
+
+impl LocalArtifact {
+    fn new(url: String, location: String, contents: Vec<u8>) -> Self {
+        LocalArtifact {
+            url,
+            location,
+            contents,
+            headers: vec![],
+            debug_id: None,
+        }
+    }
+
+    pub fn is_sourcemap(&self) -> bool {
+        sourcemap::is_sourcemap_slice(&self.contents)
+    }
+
+    /// Returns the sourcemap reference from the headers or the file contents.
+    pub fn sourcemap_reference(&self) -> Option<String> {
+        let headers = self.headers.iter().map(|(k, v)| (k, v));
+        if let Some(reference) = get_sourcemap_reference_from_headers(headers) {
+            return Some(reference.to_string());
+        }
+        sourcemap::locate_sourcemap_reference_slice(&self.contents)
+            .ok()
+            .flatten()
+            .map(|sm_ref| sm_ref.get_url().to_string())
+    }
+
+    fn detect_debug_id(&mut self) {
+        let header = self
+            .headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("debug-id"))
+            .and_then(|(_, v)| v.parse().ok());
+        self.debug_id = header.or_else(|| {
+            if self.is_sourcemap() {
+                get_debug_id_from_sourcemap(&self.contents)
+            } else {
+                get_debug_id_from_minified(&self.contents)
+            }
+        });
+    }
+}
+
+/// Release files read from disk instead of the server.
+#[derive(Debug, Default)]
+pub struct LocalArtifacts {
+    pub artifacts: Vec<LocalArtifact>,
+    pub release: Option<String>,
+    pub dist: Option<String>,
+}
+
+/// Converts a URL into the `~/path` form that matches any host.
+///
+/// Relative URLs are resolved against the root.
+pub fn tilde_url(url: &str) -> Option<String> {
+    let url = match Url::parse(url) {
+        Ok(url) => url,
+        Err(_) => Url::parse("http://example.com").unwrap().join(url).ok()?,
+    };
+    Some(format!("~{}", url.path()))
+}
+
+impl LocalArtifacts {
+    /// Reads artifacts from a directory or an artifact bundle.
+    ///
+    /// Files in a directory get URLs relative to the directory, prefixed with
+    /// `url_prefix`, just like with `sourcemaps upload`.
+    pub fn open(path: &Path, url_prefix: &str) -> Result<Self> {
+        if path.is_dir() {
+            LocalArtifacts::from_dir(path, url_prefix)
+        } else {
+            LocalArtifacts::from_bundle(path)
+        }
+    }
+
+    pub fn from_dir(path: &Path, url_prefix: &str) -> Result<Self> {
+        let url_prefix = url_prefix.strip_suffix('/').unwrap_or(url_prefix);
+        let mut artifacts = vec![];
+        for entry in WalkDir::new(path).follow_links(true).sort_by_file_name() {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let local_path = entry.path().strip_prefix(path).unwrap();
+            let mut artifact = LocalArtifact::new(
+                format!("{}/{}", url_prefix, path_as_url(local_path)),
+                entry.path().display().to_string(),
+                fs::read(entry.path())
+                    .with_context(|| format!("Could not read {}", entry.path().display()))?,
+            );
+            artifact.detect_debug_id();
+            artifacts.push(artifact);
+        }
+
+        Ok(LocalArtifacts {
+            artifacts,
+            ..Default::default()
+        })
+    }
+
+    pub fn from_bundle(path: &Path) -> Result<Self> {
+        let attributes = read_artifact_bundle_attributes(path)?;
+        let view = ByteView::open(path)?;
+        if !SourceBundle::test(&view) {
+            bail!("{} is not an artifact bundle", path.display());
+        }
+        let mut archive = ZipArchive::new(Cursor::new(&*view))?;
+        let manifest: Value = serde_json::from_reader(archive.by_name("manifest.json")?)?;
+
+        let mut artifacts = vec![];
+        if let Some(files) = manifest.get("files").and_then(Value::as_object) {
+            for (zip_path, info) in files {
+                let url = match info.get("url").and_then(Value::as_str) {
+                    Some(url) => url.to_string(),
+                    None => continue,
+                };
+                let mut contents = vec![];
+                archive.by_name(zip_path)?.read_to_end(&mut contents)?;
+
+                let mut artifact =
+                    LocalArtifact::new(url, format!("{}:{}", path.display(), zip_path), contents);
+                if let Some(headers) = info.get("headers").and_then(Value::as_object) {
+                    artifact.headers = headers
+                        .iter()
+                        .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                        .collect();
+                }
+                artifact.detect_debug_id();
+                artifacts.push(artifact);
+            }
+        }
+
+        Ok(LocalArtifacts {
+            artifacts,
+            release: attributes.get("release").cloned(),
+            dist: attributes.get("dist").cloned(),
+        })
+    }
+
+    /// Returns the artifact with exactly the given URL.
+    pub fn get(&self, url: &str) -> Option<&LocalArtifact> {
+        self.artifacts.iter().find(|a| a.url == url)
+    }
+
+    /// Finds the artifact for a URL as it appears in a stack trace, either
+    /// by exact URL or by its path with the `~` prefix.
+    pub fn find_by_url(&self, url: &str) -> Option<&LocalArtifact> {
+        self.get(url).or_else(|| self.get(&tilde_url(url)?))
+    }
+
+    /// Finds an artifact with the same file name as the given URL, as a hint
+    /// when the URL prefix does not match.
+    pub fn find_by_filename(&self, url: &str) -> Option<&LocalArtifact> {
+        let filename = url.rsplit('/').next()?;
+        self.artifacts
+            .iter()
+            .find(|a| a.url.rsplit('/').next() == Some(filename))
+    }
+
+    /// Finds the sourcemap of a minified artifact, preferring the debug ID
+    /// over the sourcemap reference.
+    pub fn find_sourcemap(&self, minified: &LocalArtifact) -> Result<&LocalArtifact> {
+        if let Some(debug_id) = minified.debug_id {
+            if let Some(sourcemap) = self
+                .artifacts
+                .iter()
+                .find(|a| a.debug_id == Some(debug_id) && a.is_sourcemap())
+            {
+                return Ok(sourcemap);
+            }
+        }
+
+        let reference = match minified.sourcemap_reference() {
+            Some(reference) => reference,
+            None => bail!("{} has no sourcemap reference", minified.url),
+        };
+        if reference.starts_with("data:") {
+            bail!(
+                "{} has an inline sourcemap, which is not supported",
+                minified.url
+            );
+        }
+        let url = join_url(&minified.url, &reference)?;
+        self.find_by_url(&url)
+            .with_context(|| format!("sourcemap {} of {} not found", url, minified.url))
+    }
+}
+
+#[test]
+fn test_tilde_url() {
+    assert_eq!(
+        tilde_url("http://localhost:5000/dist/bundle.min.js").as_deref(),
+        Some("~/dist/bundle.min.js")
+    );
+    assert_eq!(
+        tilde_url("/dist/bundle.js.map").as_deref(),
+        Some("~/dist/bundle.js.map")
+    );
+    assert_eq!(
+        tilde_url("app:///main.jsbundle").as_deref(),
+        Some("~/main.jsbundle")
+    );
+}
@@ -6,6 +6,8 @@ pub mod chunks;
 pub mod codepush;
 pub mod cordova;
 pub mod dif;
+pub mod dif_layout;
+pub mod dif_manifest;
 pub mod dif_upload;
 pub mod enc;
 pub mod event;
@@ -13,13 +15,25 @@ pub mod file_search;
 pub mod file_upload;
 pub mod formatting;
 pub mod fs;
+pub mod hermes;
 pub mod http;
+pub mod issues;
+pub mod local_artifacts;
 pub mod logging;
+pub mod monitors;
 pub mod progress;
 pub mod releases;
 pub mod retry;
+pub mod sourcemap_compose;
+pub mod sourcemap_detect;
+pub mod sourcemap_optimize;
+pub mod sourcemap_sources;
+pub mod sourcemap_validation;
 pub mod sourcemaps;
+pub mod spool;
+pub mod symbol_server;
 pub mod system;
+pub mod transactions;
 pub mod ui;
 pub mod update;
 pub mod vcs;
@@ -0,0 +1,178 @@
+use anyhow::{bail, format_err, Result};
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::{Monitor, MonitorConfig, MonitorSchedule};
+use crate::utils::args::validate_int;
+
+const INTERVAL_UNITS: &[&str] = &["minute", "hour", "day", "week", "month", "year"];
+
+/// The values the server uses for config values that are not set.
+const DEFAULT_CHECKIN_MARGIN: u64 = 1;
+const DEFAULT_MAX_RUNTIME: u64 = 30;
+const DEFAULT_TIMEZONE: &str = "UTC";
+
+/// Parses an interval schedule such as `"10 minutes"` or `"1 hour"`.
+pub fn parse_interval(value: &str) -> Result<MonitorSchedule> {
+    let mut iter = value.split_whitespace();
+    let (count, unit) = match (iter.next(), iter.next(), iter.next()) {
+        (Some(count), Some(unit), None) => (count, unit),
+        _ => bail!("Invalid interval. Expected a number and a unit, e.g. '10 minutes'."),
+    };
+    let count = count
+        .parse::<u64>()
+        .map_err(|_| format_err!("Invalid interval. '{}' is not a number.", count))?;
+    if count == 0 {
+        bail!("Invalid interval. The interval must be at least 1.");
+    }
+    let unit = unit.trim_end_matches('s');
+    if !INTERVAL_UNITS.contains(&unit) {
+        bail!(
+            "Invalid interval unit '{}'. Expected one of: {}.",
+            unit,
+            INTERVAL_UNITS.join(", ")
+        );
+    }
+    Ok(MonitorSchedule::Interval(count, unit.to_string()))
+}
+
+fn validate_interval(value: &str) -> Result<(), String> {
+    parse_interval(value).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Adds the arguments describing a monitor's schedule and thresholds.
+pub fn add_monitor_config_args(command: Command<'_>) -> Command<'_> {
+    command
+        .arg(
+            Arg::new("schedule")
+                .long("schedule")
+                .value_name("CRONTAB")
+                .conflicts_with("interval")
+                .help("Run the monitor on a crontab schedule, e.g. '0 * * * *'."),
+        )
+        .arg(
+            Arg::new("interval")
+                .long("interval")
+                .value_name("INTERVAL")
+                .validator(validate_interval)
+                .help("Run the monitor on an interval, e.g. '10 minutes' or '1 day'."),
+        )
+        .arg(
+            Arg::new("checkin_margin")
+                .long("checkin-margin")
+                .value_name("MINUTES")
+                .validator(validate_int)
+                .help("Minutes a check-in may be late before the monitor is marked as missed."),
+        )
+        .arg(
+            Arg::new("max_runtime")
+                .long("max-runtime")
+                .value_name("MINUTES")
+                .validator(validate_int)
+                .help("Minutes a check-in may stay in progress before it is marked as timed out."),
+        )
+        .arg(
+            Arg::new("timezone")
+                .long("timezone")
+                .value_name("TIMEZONE")
+                .help("The timezone of the crontab schedule, e.g. 'Europe/Vienna'."),
+        )
+}
+
+/// Builds a monitor config from the arguments added by
+/// `add_monitor_config_args`.  Only values given on the command line are set.
+pub fn get_monitor_config_from_matches(matches: &ArgMatches) -> Result<MonitorConfig> {
+    let schedule = if let Some(crontab) = matches.value_of("schedule") {
+        Some(MonitorSchedule::Crontab(crontab.to_string()))
+    } else if let Some(interval) = matches.value_of("interval") {
+        Some(parse_interval(interval)?)
+    } else {
+        None
+    };
+
+    Ok(MonitorConfig {
+        schedule_type: schedule.as_ref().map(MonitorSchedule::schedule_type),
+        schedule,
+        checkin_margin: match matches.value_of("checkin_margin") {
+            Some(value) => Some(value.parse()?),
+            None => None,
+        },
+        max_runtime: match matches.value_of("max_runtime") {
+            Some(value) => Some(value.parse()?),
+            None => None,
+        },
+        timezone: matches.value_of("timezone").map(str::to_string),
+    })
+}
+
+/// Returns `true` if any value declared in `wanted` differs from `existing`.
+/// Values that are not declared in `wanted` are ignored.
+pub fn config_differs(existing: &MonitorConfig, wanted: &MonitorConfig) -> bool {
+    (wanted.schedule.is_some() && existing.schedule != wanted.schedule)
+        || (wanted.checkin_margin.is_some() && existing.checkin_margin != wanted.checkin_margin)
+        || (wanted.max_runtime.is_some() && existing.max_runtime != wanted.max_runtime)
+        || (wanted.timezone.is_some() && existing.timezone != wanted.timezone)
+}
+
+/// Fills the config values that are not set with the defaults of the server,
+/// so that two configs can be compared in full.
+pub fn with_config_defaults(mut config: MonitorConfig) -> MonitorConfig {
+    config.checkin_margin.get_or_insert(DEFAULT_CHECKIN_MARGIN);
+    config.max_runtime.get_or_insert(DEFAULT_MAX_RUNTIME);
+    config
+        .timezone
+        .get_or_insert_with(|| DEFAULT_TIMEZONE.to_string());
+    config
+}
+
+/// Prints the details of a single monitor.
+pub fn print_monitor(monitor: &Monitor) {
+    println!("Monitor: {}", monitor.name);
+    println!("  ID: {}", monitor.id);
+    println!("  Slug: {}", monitor.slug.as_deref().unwrap_or("-"));
+    println!("  Status: {}", monitor.status);
+    match monitor.config.schedule {
+        Some(ref schedule) => println!("  Schedule: {}", schedule),
+        None => println!("  Schedule: -"),
+    }
+    if let Some(ref timezone) = monitor.config.timezone {
+        println!("  Timezone: {}", timezone);
+    }
+    if let Some(margin) = monitor.config.checkin_margin {
+        println!("  Check-in margin: {} minutes", margin);
+    }
+    if let Some(max_runtime) = monitor.config.max_runtime {
+        println!("  Max runtime: {} minutes", max_runtime);
+    }
+    if let Some(last_checkin) = monitor.last_checkin {
+        println!("  Last check-in: {}", last_checkin);
+    }
+    if let Some(next_checkin) = monitor.next_checkin {
+        println!("  Next check-in: {}", next_checkin);
+    }
+}
+
+#[test]
+fn test_parse_interval() {
+    assert_eq!(
+        parse_interval("10 minutes").unwrap(),
+        MonitorSchedule::Interval(10, "minute".into())
+    );
+    assert_eq!(
+        parse_interval("1 day").unwrap(),
+        MonitorSchedule::Interval(1, "day".into())
+    );
+    assert!(parse_interval("10").is_err());
+    assert!(parse_interval("0 hours").is_err());
+    assert!(parse_interval("5 fortnights").is_err());
+}
+
+#[test]
+fn test_with_config_defaults() {
+    let config = with_config_defaults(MonitorConfig {
+        max_runtime: Some(60),
+        ..Default::default()
+    });
+    assert_eq!(config.checkin_margin, Some(DEFAULT_CHECKIN_MARGIN));
+    assert_eq!(config.max_runtime, Some(60));
+    assert_eq!(config.timezone.as_deref(), Some(DEFAULT_TIMEZONE));
+}
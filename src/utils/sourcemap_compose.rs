@@ -0,0 +1,391 @@
+//! Composes the sourcemaps of multi-stage builds into a single sourcemap.
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use log::debug;
+use sourcemap::{DecodedMap, SourceMap, SourceMapBuilder};
+use url::Url;
+
+use crate::utils::fs::path_as_url;
+use crate::utils::sourcemaps::{find_sourcemap_path, resolve_source_path};
+
+/// How many stages are followed at most when composing sourcemaps found on disk.
+const MAX_STAGES: usize = 16;
+
+/// Decodes a sourcemap, flattening indexed sourcemaps.
+pub fn decode_flat_sourcemap(contents: &[u8]) -> Result<SourceMap> {
+    Ok(match sourcemap::decode_slice(contents)? {
+        DecodedMap::Regular(sm) => sm,
+        DecodedMap::Index(smi) => smi.flatten()?,
+        DecodedMap::Hermes(smh) => SourceMap::clone(&smh),
+    })
+}
+
+/// Maps the sources of `outer` through the sourcemaps of an earlier build stage.
+///
+/// `inner` holds the sourcemap for every source of `outer` that was itself
+/// generated. Other sources are kept as they are. Positions that the earlier
+/// stage does not map lose their source.
+pub fn compose_sourcemaps(outer: &SourceMap, inner: &HashMap<&str, &SourceMap>) -> SourceMap {
+    let mut builder = SourceMapBuilder::new(outer.get_file());
+    for token in outer.tokens() {
+        let (dst_line, dst_col) = (token.get_dst_line(), token.get_dst_col());
+        let inner_map = match token.get_source().and_then(|source| inner.get(source)) {
+            Some(inner_map) => inner_map,
+            None => {
+                let raw = builder.add_token(&token, true);
+                if token.has_source() && !builder.has_source_contents(raw.src_id) {
+                    let contents = outer.get_source_contents(token.get_src_id());
+                    builder.set_source_contents(raw.src_id, contents);
+                }
+                continue;
+            }
+        };
+
+        let inner_token = inner_map
+            .lookup_token(token.get_src_line(), token.get_src_col())
+            .filter(|t| t.get_dst_line() == token.get_src_line() && t.has_source());
+        match inner_token {
+            Some(inner_token) => {
+                let raw = builder.add(
+                    dst_line,
+                    dst_col,
+                    inner_token.get_src_line(),
+                    inner_token.get_src_col(),
+                    inner_token.get_source(),
+                    inner_token.get_name().or_else(|| token.get_name()),
+                );
+                if !builder.has_source_contents(raw.src_id) {
+                    let contents = inner_map.get_source_contents(inner_token.get_src_id());
+                    builder.set_source_contents(raw.src_id, contents);
+                }
+            }
+            None => {
+                builder.add_raw(dst_line, dst_col, 0, 0, None, None);
+            }
+        }
+    }
+    builder.into_sourcemap()
+}
+
+/// Returns whether the sourcemap of an earlier stage applies to a source of
+/// the next stage, `outer`.
+///
+/// A sourcemap without a `file` field only applies if `outer` has a single
+/// source, as it cannot be told apart from the others otherwise.
+fn is_stage_of(inner: &SourceMap, outer: &SourceMap, source: &str) -> bool {
+    let filename = |url: &str| url.rsplit('/').next().unwrap_or(url).to_string();
+    match inner.get_file() {
+        Some(file) => filename(file) == filename(source),
+        None => outer.get_source_count() == 1,
+    }
+}
+
+/// Returns the sources of `composed` that `inner` applies to.  `outer` is the
+/// sourcemap of the next stage that `composed` was built from.
+fn stage_sources<'a>(
+    composed: &'a SourceMap,
+    outer: &SourceMap,
+    inner: &'a SourceMap,
+) -> HashMap<&'a str, &'a SourceMap> {
+    composed
+        .sources()
+        .filter(|source| is_stage_of(inner, outer, source))
+        .map(|source| (source, inner))
+        .collect()
+}
+
+/// Composes the sourcemaps of a build given in build order, from the
+/// first stage to the last.
+pub fn compose_chain(maps: &[SourceMap]) -> Result<SourceMap> {
+    let (last, earlier) = match maps.split_last() {
+        Some(split) => split,
+        None => bail!("No sourcemaps to compose"),
+    };
+    let mut composed = last.clone();
+    for (idx, inner) in earlier.iter().enumerate().rev() {
+        let sources = stage_sources(&composed, &maps[idx + 1], inner);
+        if sources.is_empty() {
+            bail!(
+                "Sourcemap {} (`file` {}) does not match any source of the next stage",
+                idx + 1,
+                inner.get_file().unwrap_or("[none]")
+            );
+        }
+        composed = compose_sourcemaps(&composed, &sources);
+    }
+    Ok(composed)
+}
+
+/// A source location as source name, line and column.
+type Location<'a> = (&'a str, u32, u32);
+
+/// The locations of a sourcemap by generated line.
+///
+/// Built by scanning all tokens, so that verifying a composed sourcemap does
+/// not rely on the lookup used to compose it.
+struct LineIndex<'a>(HashMap<u32, Vec<(u32, Option<Location<'a>>)>>);
+
+impl<'a> LineIndex<'a> {
+    fn new(sm: &'a SourceMap) -> LineIndex<'a> {
+        let mut lines: HashMap<_, Vec<_>> = HashMap::new();
+        for token in sm.tokens() {
+            let location = token
+                .get_source()
+                .map(|source| (source, token.get_src_line(), token.get_src_col()));
+            lines
+                .entry(token.get_dst_line())
+                .or_default()
+                .push((token.get_dst_col(), location));
+        }
+        LineIndex(lines)
+    }
+
+    /// Returns the location of the closest mapping at or before the position
+    /// on the same line.
+    fn resolve(&self, line: u32, col: u32) -> Option<Location<'a>> {
+        self.0
+            .get(&line)?
+            .iter()
+            .filter(|(dst_col, _)| *dst_col <= col)
+            .max_by_key(|(dst_col, _)| *dst_col)
+            .and_then(|(_, location)| *location)
+    }
+}
+
+/// Resolves positions of the last stage through every stage one by one and
+/// checks that the composed sourcemap resolves them to the same location.
+///
+/// Returns the number of checked positions.
+pub fn verify_chain(maps: &[SourceMap], composed: &SourceMap, samples: usize) -> Result<usize> {
+    let (last, earlier) = match maps.split_last() {
+        Some(split) => split,
+        None => return Ok(0),
+    };
+    let step = (last.get_token_count() as usize / samples.max(1)).max(1);
+    let stages: Vec<_> = earlier.iter().map(LineIndex::new).collect();
+    let composed_index = LineIndex::new(composed);
+
+    let mut checked = 0;
+    for token in last.tokens().step_by(step) {
+        let (dst_line, dst_col) = (token.get_dst_line(), token.get_dst_col());
+
+        let mut expected = token
+            .get_source()
+            .map(|source| (source, token.get_src_line(), token.get_src_col()));
+        for (idx, stage) in stages.iter().enumerate().rev() {
+            expected = match expected {
+                Some((source, line, col)) if is_stage_of(&earlier[idx], &maps[idx + 1], source) => {
+                    stage.resolve(line, col)
+                }
+                other => other,
+            };
+        }
+
+        let actual = composed_index.resolve(dst_line, dst_col);
+
+        if expected != actual {
+            let describe = |location: Option<Location<'_>>| match location {
+                Some((source, line, col)) => format!("{}:{}:{}", source, line + 1, col + 1),
+                None => "no location".to_string(),
+            };
+            bail!(
+                "Position {}:{} resolves to {} through the individual stages, but to {} \
+                 in the composed sourcemap",
+                dst_line + 1,
+                dst_col + 1,
+                describe(expected),
+                describe(actual)
+            );
+        }
+        checked += 1;
+    }
+    Ok(checked)
+}
+
+/// Removes `.` and resolves `..` components without touching the file system.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut rv = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match rv.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    rv.pop();
+                }
+                _ => rv.push(".."),
+            },
+            other => rv.push(other),
+        }
+    }
+    rv
+}
+
+/// Expresses `path` relative to the directory `base`.
+fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let path = normalize_path(path);
+    let base = normalize_path(base);
+    let common = path
+        .components()
+        .zip(base.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut rv = PathBuf::new();
+    for _ in base.components().skip(common) {
+        rv.push("..");
+    }
+    for component in path.components().skip(common) {
+        rv.push(component);
+    }
+    rv
+}
+
+/// Rewrites relative sources of a sourcemap in `from_dir` so that they are
+/// relative to `to_dir`.
+fn relocate_sources(sm: &mut SourceMap, from_dir: &Path, to_dir: &Path) {
+    for idx in 0..sm.get_source_count() {
+        let source = match sm.get_source(idx) {
+            Some(source) if Url::parse(source).is_err() && !Path::new(source).is_absolute() => {
+                source.to_string()
+            }
+            _ => continue,
+        };
+        let relocated = relative_path(&from_dir.join(&source), to_dir);
+        sm.set_source(idx, &path_as_url(&relocated));
+    }
+}
+
+/// Inlines the contents of sources found on disk, relative to the sourcemap.
+fn load_source_contents(sm: &mut SourceMap, sourcemap_path: &Path) {
+    for idx in 0..sm.get_source_count() {
+        if sm.get_source_contents(idx).is_some() {
+            continue;
+        }
+        let contents = sm
+            .get_source(idx)
+            .and_then(|source| resolve_source_path(sourcemap_path, source))
+            .and_then(|path| fs::read_to_string(path).ok());
+        if let Some(contents) = contents {
+            sm.set_source_contents(idx, Some(&contents));
+        }
+    }
+}
+
+/// Composes a sourcemap with the sourcemaps of its sources found on disk.
+///
+/// Sources are looked up relative to the sourcemap. If a source references a
+/// sourcemap or has a `.map` file next to it, it is an intermediate build
+/// output and is replaced by the sources of that sourcemap, recursively.
+/// Returns `None` if no source has a sourcemap.
+pub fn compose_with_sourcemaps_on_disk(
+    sm: &SourceMap,
+    sourcemap_path: &Path,
+) -> Result<Option<SourceMap>> {
+    compose_on_disk(sm, sourcemap_path, 1)
+}
+
+fn compose_on_disk(
+    sm: &SourceMap,
+    sourcemap_path: &Path,
+    stage: usize,
+) -> Result<Option<SourceMap>> {
+    if stage >= MAX_STAGES {
+        bail!(
+            "Sourcemap {} has more than {} build stages",
+            sourcemap_path.display(),
+            MAX_STAGES
+        );
+    }
+    let sourcemap_dir = sourcemap_path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut stages = HashMap::new();
+    for source in sm.sources() {
+        let source_path = match resolve_source_path(sourcemap_path, source) {
+            Some(source_path) => source_path,
+            None => continue,
+        };
+        let contents = fs::read(&source_path)
+            .with_context(|| format!("Could not read {}", source_path.display()))?;
+        let inner_path = match find_sourcemap_path(&source_path, &contents) {
+            Some(inner_path) => inner_path,
+            None => continue,
+        };
+        debug!(
+            "composing {} with {}",
+            sourcemap_path.display(),
+            inner_path.display()
+        );
+
+        let inner_contents = fs::read(&inner_path)
+            .with_context(|| format!("Could not read {}", inner_path.display()))?;
+        let mut inner = decode_flat_sourcemap(&inner_contents)
+            .with_context(|| format!("Invalid sourcemap {}", inner_path.display()))?;
+        if let Some(composed) = compose_on_disk(&inner, &inner_path, stage + 1)? {
+            inner = composed;
+        }
+        load_source_contents(&mut inner, &inner_path);
+        let inner_dir = inner_path.parent().unwrap_or_else(|| Path::new(""));
+        relocate_sources(&mut inner, inner_dir, sourcemap_dir);
+        stages.insert(source, inner);
+    }
+
+    if stages.is_empty() {
+        return Ok(None);
+    }
+    let stages = stages.iter().map(|(k, v)| (*k, v)).collect();
+    Ok(Some(compose_sourcemaps(sm, &stages)))
+}
+
+#[test]
+fn test_compose_chain() {
+    // a.ts -> a.js: the identifier `greet` moves from column 9 to column 4.
+    let first = SourceMap::from_slice(
+        br#"{"version":3,"file":"a.js","sources":["a.ts"],"names":["greet"],"mappings":"AAAA,IAASA"}"#,
+    )
+    .unwrap();
+    // a.js -> a.min.js: column 4 moves to column 2 on the second line.
+    let second = SourceMap::from_slice(
+        br#"{"version":3,"file":"a.min.js","sources":["a.js"],"names":[],"mappings":";AAAA,EAAI"}"#,
+    )
+    .unwrap();
+
+    let maps = [first, second];
+    let composed = compose_chain(&maps).unwrap();
+    let token = composed.lookup_token(1, 2).unwrap();
+    assert_eq!(token.get_source(), Some("a.ts"));
+    assert_eq!((token.get_src_line(), token.get_src_col()), (0, 9));
+    assert_eq!(token.get_name(), Some("greet"));
+    assert_eq!(verify_chain(&maps, &composed, 10).unwrap(), 2);
+}
+
+#[test]
+fn test_compose_chain_ambiguous_stage() {
+    // A stage without `file` cannot be matched to one of several sources.
+    let first =
+        SourceMap::from_slice(br#"{"version":3,"sources":["a.ts"],"names":[],"mappings":"AAAA"}"#)
+            .unwrap();
+    let second = SourceMap::from_slice(
+        br#"{"version":3,"file":"out.js","sources":["a.js","b.js"],"names":[],"mappings":"AAAA,CCAA"}"#,
+    )
+    .unwrap();
+
+    assert!(compose_chain(&[first, second]).is_err());
+}
+
+#[test]
+fn test_relative_path() {
+    assert_eq!(
+        relative_path(Path::new("build/tsc/../../src/a.ts"), Path::new("dist")),
+        Path::new("../src/a.ts")
+    );
+    assert_eq!(
+        relative_path(Path::new("dist/./a.js"), Path::new("dist")),
+        Path::new("a.js")
+    );
+    assert_eq!(
+        relative_path(Path::new("../../src/a.ts"), Path::new("dist")),
+        Path::new("../../../src/a.ts")
+    );
+}
@@ -0,0 +1,307 @@
+//! Detects the output of common JavaScript build tools.
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use serde_json::Value;
+use url::Url;
+use walkdir::WalkDir;
+
+/// Files that describe the output of a build, in the order they are checked.
+const MANIFEST_NAMES: &[&str] = &[
+    "stats.json",
+    ".vite/manifest.json",
+    "manifest.json",
+    "meta.json",
+    "metafile.json",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildTool {
+    Webpack,
+    Vite,
+    NextJs,
+    Esbuild,
+}
+
+impl fmt::Display for BuildTool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            BuildTool::Webpack => write!(f, "webpack"),
+            BuildTool::Vite => write!(f, "Vite/Rollup"),
+            BuildTool::NextJs => write!(f, "Next.js"),
+            BuildTool::Esbuild => write!(f, "esbuild"),
+        }
+    }
+}
+
+/// The minified files and sourcemaps of a detected build.
+#[derive(Debug)]
+pub struct DetectedBuild {
+    pub tool: BuildTool,
+    /// The manifest or directory the build was detected from.
+    pub source: PathBuf,
+    /// The directory that is served under `url_prefix`.
+    pub output_dir: PathBuf,
+    pub url_prefix: String,
+    pub files: Vec<PathBuf>,
+}
+
+impl DetectedBuild {
+    fn new(tool: BuildTool, source: PathBuf, output_dir: PathBuf, url_prefix: String) -> Self {
+        DetectedBuild {
+            tool,
+            source,
+            output_dir,
+            url_prefix,
+            files: vec![],
+        }
+    }
+
+    /// Adds the files relative to the output directory that exist, together
+    /// with the sourcemaps next to them.
+    fn add_files<'a, I>(&mut self, names: I)
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut files = BTreeSet::new();
+        for name in names
+            .into_iter()
+            .filter(|name| is_script_or_sourcemap(name))
+        {
+            let path = self.output_dir.join(name.trim_start_matches('/'));
+            if !path.is_file() {
+                warn!(
+                    "File {} from the build manifest does not exist",
+                    path.display()
+                );
+                continue;
+            }
+            let sourcemap = PathBuf::from(format!("{}.map", path.display()));
+            if !name.ends_with(".map") && sourcemap.is_file() {
+                files.insert(sourcemap);
+            }
+            files.insert(path);
+        }
+        self.files = files.into_iter().collect();
+    }
+
+    /// Returns the path of a file relative to the output directory.
+    pub fn relative_path<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.output_dir).unwrap_or(path)
+    }
+}
+
+fn is_script_or_sourcemap(name: &str) -> bool {
+    [".js", ".mjs", ".cjs", ".map"]
+        .iter()
+        .any(|ext| name.ends_with(ext))
+}
+
+/// Converts a public path of a bundler into a URL prefix without trailing slash.
+///
+/// Absolute URLs are reduced to their path, since `~` matches any host.
+fn public_path_to_url_prefix(public_path: &str) -> String {
+    let path = if public_path == "auto" {
+        String::new()
+    } else if let Some(rest) = public_path.strip_prefix("//") {
+        rest.split_once('/')
+            .map_or("", |(_, path)| path)
+            .to_string()
+    } else {
+        match Url::parse(public_path) {
+            Ok(url) => url.path().to_string(),
+            Err(_) => public_path.to_string(),
+        }
+    };
+    let path = path.trim_matches('/');
+    if path.is_empty() {
+        "~".to_string()
+    } else {
+        format!("~/{}", path)
+    }
+}
+
+fn detect_webpack(manifest: &Path, stats: &Value) -> Option<DetectedBuild> {
+    let assets = stats.get("assets")?.as_array()?;
+    let manifest_dir = manifest.parent().unwrap_or_else(|| Path::new(""));
+
+    // The stats contain the absolute output path of the machine that ran the
+    // build. Fall back to a directory of the same name next to the stats.
+    let output_path = stats.get("outputPath").and_then(Value::as_str);
+    let output_dir = match output_path.map(Path::new) {
+        Some(path) if path.is_dir() => path.to_path_buf(),
+        Some(path) => path
+            .file_name()
+            .map(|name| manifest_dir.join(name))
+            .filter(|dir| dir.is_dir())
+            .unwrap_or_else(|| manifest_dir.to_path_buf()),
+        None => manifest_dir.to_path_buf(),
+    };
+    let public_path = stats
+        .get("publicPath")
+        .and_then(Value::as_str)
+        .unwrap_or("auto");
+
+    let mut build = DetectedBuild::new(
+        BuildTool::Webpack,
+        manifest.to_path_buf(),
+        output_dir,
+        public_path_to_url_prefix(public_path),
+    );
+    build.add_files(
+        assets
+            .iter()
+            .filter_map(|asset| asset.get("name").and_then(Value::as_str)),
+    );
+    Some(build)
+}
+
+fn detect_vite(manifest: &Path, chunks: &Value) -> Option<DetectedBuild> {
+    let chunks = chunks.as_object()?;
+    if chunks.is_empty() || !chunks.values().all(|chunk| chunk.get("file").is_some()) {
+        return None;
+    }
+
+    // Vite 5 writes the manifest to `.vite/manifest.json` inside the output
+    // directory, earlier versions and Rollup plugins next to the output.
+    let mut output_dir = manifest.parent().unwrap_or_else(|| Path::new(""));
+    if output_dir.file_name() == Some(OsStr::new(".vite")) {
+        output_dir = output_dir.parent().unwrap_or_else(|| Path::new(""));
+    }
+
+    // The manifest does not record the `base` option, which defaults to `/`.
+    let mut build = DetectedBuild::new(
+        BuildTool::Vite,
+        manifest.to_path_buf(),
+        output_dir.to_path_buf(),
+        "~".to_string(),
+    );
+    build.add_files(
+        chunks
+            .values()
+            .filter_map(|chunk| chunk.get("file").and_then(Value::as_str)),
+    );
+    Some(build)
+}
+
+fn detect_esbuild(manifest: &Path, metafile: &Value) -> Option<DetectedBuild> {
+    metafile.get("inputs")?.as_object()?;
+    let outputs = metafile.get("outputs")?.as_object()?;
+
+    // Outputs are relative to the working directory of esbuild, which is
+    // assumed to be the directory of the metafile. The output directory is
+    // the deepest directory that contains all outputs.
+    let manifest_dir = manifest.parent().unwrap_or_else(|| Path::new(""));
+    let mut output_dir: Option<PathBuf> = None;
+    for name in outputs.keys().filter(|name| is_script_or_sourcemap(name)) {
+        let dir = Path::new(name).parent().unwrap_or_else(|| Path::new(""));
+        output_dir = Some(match output_dir {
+            None => dir.to_path_buf(),
+            Some(common) => common
+                .components()
+                .zip(dir.components())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+    let output_dir = output_dir.unwrap_or_default();
+
+    let mut build = DetectedBuild::new(
+        BuildTool::Esbuild,
+        manifest.to_path_buf(),
+        manifest_dir.join(&output_dir),
+        "~".to_string(),
+    );
+    let names: Vec<_> = outputs
+        .keys()
+        .filter_map(|name| Path::new(name).strip_prefix(&output_dir).ok())
+        .filter_map(Path::to_str)
+        .collect();
+    build.add_files(names);
+    Some(build)
+}
+
+fn detect_next(path: &Path) -> Option<DetectedBuild> {
+    let next_dir = if path.file_name() == Some(OsStr::new(".next")) {
+        path.to_path_buf()
+    } else {
+        path.join(".next")
+    };
+    let static_dir = next_dir.join("static");
+    if !static_dir.is_dir() {
+        return None;
+    }
+
+    let mut build = DetectedBuild::new(
+        BuildTool::NextJs,
+        next_dir,
+        static_dir.clone(),
+        "~/_next/static".to_string(),
+    );
+    let names: Vec<_> = WalkDir::new(&static_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let path = entry.path().strip_prefix(&static_dir).ok()?;
+            path.to_str().map(str::to_string)
+        })
+        .collect();
+    build.add_files(names.iter().map(String::as_str));
+    Some(build)
+}
+
+/// Detects a build from a webpack stats file, a Vite or Rollup manifest or
+/// an esbuild metafile.
+pub fn detect_build_from_manifest(manifest: &Path) -> Result<Option<DetectedBuild>> {
+    let contents =
+        fs::read(manifest).with_context(|| format!("Could not read {}", manifest.display()))?;
+    let value: Value = match serde_json::from_slice(&contents) {
+        Ok(value) => value,
+        Err(err) => {
+            debug!("skipping {}: {}", manifest.display(), err);
+            return Ok(None);
+        }
+    };
+    Ok(detect_webpack(manifest, &value)
+        .or_else(|| detect_esbuild(manifest, &value))
+        .or_else(|| detect_vite(manifest, &value)))
+}
+
+/// Detects the build in a project or output directory, or from a manifest.
+pub fn detect_build(path: &Path) -> Result<Option<DetectedBuild>> {
+    if path.is_file() {
+        return detect_build_from_manifest(path);
+    }
+    if let Some(build) = detect_next(path) {
+        return Ok(Some(build));
+    }
+    for name in MANIFEST_NAMES {
+        let manifest = path.join(name);
+        if manifest.is_file() {
+            if let Some(build) = detect_build_from_manifest(&manifest)? {
+                return Ok(Some(build));
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[test]
+fn test_public_path_to_url_prefix() {
+    assert_eq!(public_path_to_url_prefix("auto"), "~");
+    assert_eq!(public_path_to_url_prefix("/"), "~");
+    assert_eq!(public_path_to_url_prefix("/static/js/"), "~/static/js");
+    assert_eq!(public_path_to_url_prefix("assets/"), "~/assets");
+    assert_eq!(
+        public_path_to_url_prefix("https://cdn.example.com/app/"),
+        "~/app"
+    );
+    assert_eq!(public_path_to_url_prefix("//cdn.example.com/app/"), "~/app");
+}
@@ -0,0 +1,126 @@
+//! Reduces the size of sourcemaps before they are uploaded.
+use anyhow::{Context, Result};
+use glob::Pattern;
+use sourcemap::{SourceMap, SourceMapBuilder};
+
+/// Configures which parts of sourcemaps are removed by the optimization pass.
+#[derive(Debug, Default)]
+pub struct OptimizeOptions {
+    strip_sources: Vec<Pattern>,
+    strip_sources_content: Vec<Pattern>,
+    dedupe_sources_content: bool,
+}
+
+fn parse_patterns<'a, I>(patterns: I) -> Result<Vec<Pattern>>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    patterns
+        .into_iter()
+        .map(|pattern| {
+            Pattern::new(pattern).with_context(|| format!("Invalid glob pattern {}", pattern))
+        })
+        .collect()
+}
+
+impl OptimizeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes the mappings to sources matching any of the globs.
+    pub fn strip_sources<'a, I>(&mut self, patterns: I) -> Result<&mut Self>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.strip_sources.extend(parse_patterns(patterns)?);
+        Ok(self)
+    }
+
+    /// Removes the contents of sources matching any of the globs.
+    pub fn strip_sources_content<'a, I>(&mut self, patterns: I) -> Result<&mut Self>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.strip_sources_content.extend(parse_patterns(patterns)?);
+        Ok(self)
+    }
+
+    /// Moves contents shared by several sourcemaps into a single release file.
+    pub fn dedupe_sources_content(&mut self, dedupe: bool) -> &mut Self {
+        self.dedupe_sources_content = dedupe;
+        self
+    }
+
+    pub fn dedupes_sources_content(&self) -> bool {
+        self.dedupe_sources_content
+    }
+
+    fn strips_source(&self, source: &str) -> bool {
+        self.strip_sources.iter().any(|p| p.matches(source))
+    }
+
+    fn strips_source_content(&self, source: &str) -> bool {
+        self.strip_sources_content.iter().any(|p| p.matches(source))
+    }
+}
+
+/// Rebuilds a sourcemap from its mappings.
+///
+/// Names and sources that no mapping refers to are dropped, as are the
+/// mappings and contents that the options strip.
+pub fn optimize_sourcemap(sm: &SourceMap, options: &OptimizeOptions) -> SourceMap {
+    let mut builder = SourceMapBuilder::new(sm.get_file());
+    for token in sm.tokens() {
+        if matches!(token.get_source(), Some(source) if options.strips_source(source)) {
+            // Keep the position so that the previous mapping does not extend
+            // over the stripped code.
+            builder.add_raw(token.get_dst_line(), token.get_dst_col(), 0, 0, None, None);
+            continue;
+        }
+
+        let raw = builder.add_token(&token, true);
+        if !token.has_source() || builder.has_source_contents(raw.src_id) {
+            continue;
+        }
+        let source = token.get_source().unwrap_or_default();
+        if !options.strips_source_content(source) {
+            builder.set_source_contents(raw.src_id, sm.get_source_contents(token.get_src_id()));
+        }
+    }
+    builder.into_sourcemap()
+}
+
+#[test]
+fn test_optimize_sourcemap() {
+    let sm = SourceMap::from_slice(
+        br#"{
+            "version": 3,
+            "sources": ["src/a.js", "node_modules/b/index.js", "unused.js"],
+            "sourcesContent": ["a();", "b();", "unused();"],
+            "names": ["a", "b", "unused"],
+            "mappings": "AAAAA,ICAAC"
+        }"#,
+    )
+    .unwrap();
+
+    let optimized = optimize_sourcemap(&sm, &OptimizeOptions::new());
+    assert_eq!(
+        optimized.sources().collect::<Vec<_>>(),
+        ["src/a.js", "node_modules/b/index.js"]
+    );
+    assert_eq!(optimized.names().collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(optimized.get_source_contents(1), Some("b();"));
+
+    let mut options = OptimizeOptions::new();
+    options.strip_sources_content(["*node_modules*"]).unwrap();
+    let optimized = optimize_sourcemap(&sm, &options);
+    assert_eq!(optimized.get_source_count(), 2);
+    assert_eq!(optimized.get_source_contents(1), None);
+
+    let mut options = OptimizeOptions::new();
+    options.strip_sources(["*node_modules*"]).unwrap();
+    let optimized = optimize_sourcemap(&sm, &options);
+    assert_eq!(optimized.sources().collect::<Vec<_>>(), ["src/a.js"]);
+    assert!(!optimized.lookup_token(0, 4).unwrap().has_source());
+}
@@ -0,0 +1,255 @@
+//! Locates the original sources of sourcemaps that lack `sourcesContent`.
+use std::cmp::Reverse;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{bail, format_err, Context, Error, Result};
+use git2::{Oid, Repository};
+use log::debug;
+use sourcemap::SourceMap;
+use url::Url;
+
+use crate::utils::sourcemap_compose::normalize_path;
+
+/// Maps sources starting with a prefix to a local directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceRoot {
+    pub prefix: String,
+    pub dir: PathBuf,
+}
+
+impl FromStr for SourceRoot {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<SourceRoot> {
+        match s.split_once('=') {
+            Some((prefix, dir)) if !prefix.is_empty() => Ok(SourceRoot {
+                prefix: prefix.to_string(),
+                dir: PathBuf::from(if dir.is_empty() { "." } else { dir }),
+            }),
+            _ => bail!("Invalid source root '{}', expected PREFIX=DIR", s),
+        }
+    }
+}
+
+/// Reads files from a commit of a git repository.
+struct GitSources {
+    repo: Repository,
+    commit: Oid,
+    workdir: PathBuf,
+}
+
+impl GitSources {
+    fn read(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.workdir).ok()?;
+        let tree = self.repo.find_commit(self.commit).ok()?.tree().ok()?;
+        let entry = tree.get_path(relative).ok()?;
+        let blob = self.repo.find_blob(entry.id()).ok()?;
+        String::from_utf8(blob.content().to_vec()).ok()
+    }
+}
+
+/// Where a source was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceLocation {
+    Disk,
+    Git,
+}
+
+/// Resolves the sources of sourcemaps to their contents.
+///
+/// Sources are looked up through the configured source roots and relative to
+/// the sourcemap, in that order. If a commit is configured, sources that do not
+/// exist on disk are read from it.
+#[derive(Default)]
+pub struct SourceResolver {
+    roots: Vec<SourceRoot>,
+    git: Option<GitSources>,
+}
+
+impl SourceResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn source_roots<I>(&mut self, roots: I) -> &mut Self
+    where
+        I: IntoIterator<Item = SourceRoot>,
+    {
+        self.roots.extend(roots);
+        // Prefer the most specific prefix if several match.
+        self.roots.sort_by_key(|root| Reverse(root.prefix.len()));
+        self
+    }
+
+    /// Reads sources missing on disk from a commit of the repository that
+    /// contains the working directory.
+    pub fn git_commit(&mut self, rev: &str) -> Result<&mut Self> {
+        let repo = Repository::open_from_env()
+            .context("Could not open the git repository for --source-commit")?;
+        let commit = repo
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .with_context(|| format!("Could not find commit {}", rev))?
+            .id();
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| format_err!("Cannot read sources from a bare repository"))?
+            .canonicalize()?;
+        debug!("reading missing sources from commit {}", commit);
+        self.git = Some(GitSources {
+            repo,
+            commit,
+            workdir,
+        });
+        Ok(self)
+    }
+
+    /// Returns the local paths a source may refer to.
+    fn candidate_paths(&self, sourcemap_path: &Path, source: &str) -> Vec<PathBuf> {
+        let mut rv = vec![];
+        for root in &self.roots {
+            if let Some(rest) = source.strip_prefix(&root.prefix) {
+                rv.push(normalize_path(&root.dir.join(rest.trim_start_matches('/'))));
+            }
+        }
+        match Url::parse(source) {
+            Ok(url) if url.scheme() == "file" => rv.extend(url.to_file_path().ok()),
+            Ok(_) => {}
+            Err(_) => {
+                if let Some(dir) = sourcemap_path.parent() {
+                    rv.push(normalize_path(&dir.join(source)));
+                }
+            }
+        }
+        rv
+    }
+
+    /// Reads the contents of a source from disk or the configured commit.
+    pub fn read_source(
+        &self,
+        sourcemap_path: &Path,
+        source: &str,
+    ) -> Option<(String, SourceLocation)> {
+        let candidates = self.candidate_paths(sourcemap_path, source);
+        for path in &candidates {
+            if let Ok(contents) = fs::read_to_string(path) {
+                return Some((contents, SourceLocation::Disk));
+            }
+        }
+
+        let git = self.git.as_ref()?;
+        let cwd = env::current_dir().ok()?.canonicalize().ok()?;
+        candidates.iter().find_map(|path| {
+            let contents = git.read(&normalize_path(&cwd.join(path)))?;
+            Some((contents, SourceLocation::Git))
+        })
+    }
+
+    /// Inlines the contents of all sources without `sourcesContent`.
+    pub fn inline_sources(&self, sm: &mut SourceMap, sourcemap_path: &Path) -> InlineResult {
+        let mut rv = InlineResult::default();
+        for idx in 0..sm.get_source_count() {
+            if sm.get_source_contents(idx).is_some() {
+                continue;
+            }
+            let source = match sm.get_source(idx) {
+                Some(source) => source.to_string(),
+                None => continue,
+            };
+            match self.read_source(sourcemap_path, &source) {
+                Some((contents, location)) => {
+                    sm.set_source_contents(idx, Some(&contents));
+                    match location {
+                        SourceLocation::Disk => rv.from_disk += 1,
+                        SourceLocation::Git => rv.from_git += 1,
+                    }
+                }
+                None => rv.missing.push(source),
+            }
+        }
+        rv
+    }
+}
+
+/// The outcome of inlining the sources of a sourcemap.
+#[derive(Debug, Default)]
+pub struct InlineResult {
+    pub from_disk: usize,
+    pub from_git: usize,
+    pub missing: Vec<String>,
+}
+
+impl InlineResult {
+    pub fn inlined(&self) -> usize {
+        self.from_disk + self.from_git
+    }
+}
+
+#[test]
+fn test_source_root_from_str() {
+    let root: SourceRoot = "webpack://app/=packages/app".parse().unwrap();
+    assert_eq!(root.prefix, "webpack://app/");
+    assert_eq!(root.dir, Path::new("packages/app"));
+    let root: SourceRoot = "../../=".parse().unwrap();
+    assert_eq!(root.dir, Path::new("."));
+    assert!("packages/app".parse::<SourceRoot>().is_err());
+    assert!("=packages/app".parse::<SourceRoot>().is_err());
+}
+
+#[test]
+fn test_candidate_paths() {
+    let mut resolver = SourceResolver::new();
+    resolver.source_roots(vec![
+        "webpack://app/=".parse().unwrap(),
+        "webpack://app/./src/=lib".parse().unwrap(),
+    ]);
+    assert_eq!(
+        resolver.candidate_paths(Path::new("dist/app.js.map"), "webpack://app/./src/a.js"),
+        vec![PathBuf::from("lib/a.js"), PathBuf::from("src/a.js")]
+    );
+    assert_eq!(
+        resolver.candidate_paths(Path::new("dist/app.js.map"), "src/a.js"),
+        vec![PathBuf::from("dist/src/a.js")]
+    );
+    assert_eq!(
+        resolver.candidate_paths(
+            Path::new("packages/web/dist/app.js.map"),
+            "../../shared/src/a.js"
+        ),
+        vec![PathBuf::from("packages/shared/src/a.js")]
+    );
+}
+
+#[test]
+fn test_git_sources_read() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/a.js"), "a();\n").unwrap();
+
+    let commit = {
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("src/a.js")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(None, &signature, &signature, "initial", &tree, &[])
+            .unwrap()
+    };
+    fs::remove_file(dir.path().join("src/a.js")).unwrap();
+
+    let workdir = dir.path().canonicalize().unwrap();
+    let git = GitSources {
+        repo,
+        commit,
+        workdir: workdir.clone(),
+    };
+    assert_eq!(
+        git.read(&workdir.join("src/a.js")).as_deref(),
+        Some("a();\n")
+    );
+    assert_eq!(git.read(&workdir.join("src/b.js")), None);
+    assert_eq!(git.read(Path::new("/elsewhere/src/a.js")), None);
+}
@@ -0,0 +1,583 @@
+//! Deep validation of sourcemaps against their minified files and sources.
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+use elementtree::Element;
+use serde::Serialize;
+use sourcemap::{DecodedMap, SourceMap, SourceView};
+use symbolic::debuginfo::sourcebundle::SourceFileType;
+
+use crate::utils::file_upload::{LogLevel, ReleaseFile, ReleaseFiles};
+use crate::utils::sourcemaps::{
+    get_debug_id, get_sourcemap_ref, guess_sourcemap_reference, join_url, resolve_source_path,
+};
+
+/// How many occurrences of the same problem are reported per file.
+const MAX_OCCURRENCES: usize = 3;
+
+/// The formats a validation report can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Text,
+    Json,
+    Junit,
+}
+
+impl FromStr for ReportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<ReportFormat> {
+        match s {
+            "text" => Ok(ReportFormat::Text),
+            "json" => Ok(ReportFormat::Json),
+            "junit" => Ok(ReportFormat::Junit),
+            _ => bail!("unknown report format {}", s),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValidationIssue {
+    pub level: LogLevel,
+    pub check: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileReport {
+    pub url: String,
+    pub path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sourcemap: Option<String>,
+    pub issues: Vec<ValidationIssue>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ValidationReport {
+    pub errors: usize,
+    pub warnings: usize,
+    pub files: Vec<FileReport>,
+}
+
+/// Collects the issues of a single file, capping repeated problems.
+#[derive(Default)]
+struct Issues {
+    issues: Vec<ValidationIssue>,
+    counts: BTreeMap<&'static str, (LogLevel, usize)>,
+}
+
+impl Issues {
+    fn add(&mut self, level: LogLevel, check: &'static str, message: String) {
+        let count = self.counts.entry(check).or_insert((level, 0));
+        count.1 += 1;
+        if count.1 <= MAX_OCCURRENCES {
+            self.issues.push(ValidationIssue {
+                level,
+                check,
+                message,
+            });
+        }
+    }
+
+    fn error(&mut self, check: &'static str, message: String) {
+        self.add(LogLevel::Error, check, message);
+    }
+
+    fn warn(&mut self, check: &'static str, message: String) {
+        self.add(LogLevel::Warning, check, message);
+    }
+
+    fn finish(mut self) -> Vec<ValidationIssue> {
+        for (check, (level, count)) in self.counts {
+            if count > MAX_OCCURRENCES {
+                self.issues.push(ValidationIssue {
+                    level,
+                    check,
+                    message: format!("{} more problem(s) of this kind", count - MAX_OCCURRENCES),
+                });
+            }
+        }
+        self.issues
+    }
+}
+
+/// Returns the length of a line in JavaScript (UTF-16) columns.
+fn js_len(line: &str) -> u32 {
+    line.encode_utf16().count() as u32
+}
+
+/// Returns the rest of a line starting at a JavaScript (UTF-16) column.
+fn js_slice_from(line: &str, col: u32) -> Option<&str> {
+    let mut off = 0;
+    for (idx, c) in line.char_indices() {
+        if off >= col {
+            return Some(&line[idx..]);
+        }
+        off += c.len_utf16() as u32;
+    }
+    (off == col).then_some("")
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+/// Returns how many lines at the start of a file are comments or empty.
+fn count_banner_lines(view: &SourceView<'_>) -> u32 {
+    let mut in_comment = false;
+    let mut idx = 0;
+    while let Some(line) = view.get_line(idx) {
+        let line = line.trim();
+        let rest = if in_comment {
+            match line.find("*/") {
+                Some(end) => {
+                    in_comment = false;
+                    &line[end + 2..]
+                }
+                None => "",
+            }
+        } else if let Some(comment) = line.strip_prefix("/*") {
+            match comment.find("*/") {
+                Some(end) => &comment[end + 2..],
+                None => {
+                    in_comment = true;
+                    ""
+                }
+            }
+        } else if line.starts_with("//") && !line.starts_with("//#") {
+            ""
+        } else {
+            line
+        };
+        if !rest.trim().is_empty() {
+            break;
+        }
+        idx += 1;
+    }
+    idx
+}
+
+fn filename(url: &str) -> &str {
+    url.rsplit('/').next().unwrap_or(url)
+}
+
+fn normalize_newlines(s: &str) -> String {
+    s.replace("\r\n", "\n")
+}
+
+/// Checks the mappings of a sourcemap against the minified file they belong to.
+fn validate_minified_positions(issues: &mut Issues, sm: &SourceMap, minified: &ReleaseFile) {
+    if let Some(file) = sm.get_file() {
+        if filename(file) != filename(&minified.url) {
+            issues.warn(
+                "file-field",
+                format!(
+                    "`file` is {}, but the sourcemap belongs to {}",
+                    file, minified.url
+                ),
+            );
+        }
+    }
+
+    let contents = String::from_utf8_lossy(&minified.contents);
+    let view = SourceView::new(&contents);
+
+    let banner_lines = count_banner_lines(&view);
+    let first_mapped_line = sm.tokens().map(|token| token.get_dst_line()).min();
+    if let Some(first_mapped_line) = first_mapped_line.filter(|&line| line < banner_lines) {
+        issues.error(
+            "banner-offset",
+            format!(
+                "mappings start at line {}, but the first {} line(s) of {} are a comment \
+                 banner; it was probably added after the sourcemap was generated",
+                first_mapped_line + 1,
+                banner_lines,
+                minified.url
+            ),
+        );
+        // All other positions are off as well, reporting them adds nothing.
+        return;
+    }
+
+    for token in sm.tokens() {
+        let (line, col) = (token.get_dst_line(), token.get_dst_col());
+        let text = match view.get_line(line) {
+            Some(text) => text,
+            None => {
+                issues.error(
+                    "mapping-bounds",
+                    format!(
+                        "mapping at {}:{} is beyond the last line of {} ({} lines)",
+                        line + 1,
+                        col + 1,
+                        minified.url,
+                        view.line_count()
+                    ),
+                );
+                continue;
+            }
+        };
+        let rest = match js_slice_from(text, col) {
+            Some(rest) => rest,
+            None => {
+                issues.error(
+                    "mapping-bounds",
+                    format!(
+                        "mapping at {}:{} is beyond the end of the line ({} columns)",
+                        line + 1,
+                        col + 1,
+                        js_len(text)
+                    ),
+                );
+                continue;
+            }
+        };
+        if let Some(name) = token.get_name() {
+            if !rest.starts_with(is_identifier_start) {
+                issues.warn(
+                    "names",
+                    format!(
+                        "name `{}` at {}:{} does not point to an identifier",
+                        name,
+                        line + 1,
+                        col + 1
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Checks the sources of a sourcemap and the positions it maps to.
+fn validate_sources(
+    issues: &mut Issues,
+    source_urls: &HashSet<String>,
+    sm: &SourceMap,
+    sourcemap: &ReleaseFile,
+) {
+    let mut contents = vec![];
+    for idx in 0..sm.get_source_count() {
+        let source = sm.get_source(idx).unwrap_or("??");
+        let on_disk = resolve_source_path(&sourcemap.path, source);
+        let disk_contents = on_disk
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok());
+
+        match (sm.get_source_contents(idx), &disk_contents) {
+            (Some(embedded), Some(disk_contents)) => {
+                if normalize_newlines(embedded) != normalize_newlines(disk_contents) {
+                    issues.warn(
+                        "sources-content-mismatch",
+                        format!(
+                            "sourcesContent of {} differs from {}",
+                            source,
+                            on_disk.unwrap().display()
+                        ),
+                    );
+                }
+            }
+            (Some(_), None) => {}
+            (None, Some(_)) => issues.warn(
+                "sources-content",
+                format!(
+                    "missing sourcesContent for {} (found on disk, it is inlined on upload)",
+                    source
+                ),
+            ),
+            (None, None) if source_urls.contains(source) => {}
+            (None, None) => issues.warn(
+                "sources-content",
+                format!("missing sourcecode ({})", source),
+            ),
+        }
+        contents.push(
+            sm.get_source_contents(idx)
+                .map(str::to_string)
+                .or(disk_contents),
+        );
+    }
+
+    let views: Vec<_> = contents
+        .iter()
+        .map(|c| c.as_deref().map(SourceView::new))
+        .collect();
+    for token in sm.tokens() {
+        let view = match views.get(token.get_src_id() as usize) {
+            Some(Some(view)) => view,
+            _ => continue,
+        };
+        let (line, col) = (token.get_src_line(), token.get_src_col());
+        let location = format!(
+            "mapping at {}:{} points to {}:{}:{}",
+            token.get_dst_line() + 1,
+            token.get_dst_col() + 1,
+            token.get_source().unwrap_or("??"),
+            line + 1,
+            col + 1
+        );
+        match view.get_line(line) {
+            None => issues.error(
+                "source-bounds",
+                format!(
+                    "{}, beyond the last line ({} lines)",
+                    location,
+                    view.line_count()
+                ),
+            ),
+            Some(text) if col > js_len(text) => issues.error(
+                "source-bounds",
+                format!(
+                    "{}, beyond the end of the line ({} columns)",
+                    location,
+                    js_len(text)
+                ),
+            ),
+            Some(_) => {}
+        }
+    }
+}
+
+/// Finds the sourcemap of a minified file by debug ID, reference, or file name.
+fn find_sourcemap<'a>(
+    sources: &'a ReleaseFiles,
+    sourcemap_urls: &HashSet<String>,
+    file: &ReleaseFile,
+) -> Result<Option<&'a ReleaseFile>> {
+    if let Some(debug_id) = get_debug_id(file) {
+        if let Some(sourcemap) = sources
+            .values()
+            .find(|s| s.ty == SourceFileType::SourceMap && get_debug_id(s) == Some(debug_id))
+        {
+            return Ok(Some(sourcemap));
+        }
+    }
+
+    let reference = match get_sourcemap_ref(file) {
+        Some(sm_ref) => sm_ref.get_url().to_string(),
+        None if file.ty == SourceFileType::MinifiedSource => {
+            match guess_sourcemap_reference(sourcemap_urls, &file.url) {
+                Ok(reference) => reference,
+                Err(_) => bail!("missing sourcemap"),
+            }
+        }
+        None => return Ok(None),
+    };
+    if reference.starts_with("data:") {
+        return Ok(None);
+    }
+    let url = join_url(&file.url, &reference)?;
+    match sources.get(&url) {
+        Some(sourcemap) => Ok(Some(sourcemap)),
+        None => bail!("sourcemap {} not found", url),
+    }
+}
+
+fn decode_sourcemap(issues: &mut Issues, sourcemap: &ReleaseFile) -> Option<SourceMap> {
+    match sourcemap::decode_slice(&sourcemap.contents) {
+        Ok(DecodedMap::Regular(sm)) => Some(sm),
+        Ok(DecodedMap::Hermes(smh)) => Some(SourceMap::clone(&smh)),
+        Ok(DecodedMap::Index(smi)) => match smi.flatten() {
+            Ok(sm) => Some(sm),
+            Err(err) => {
+                issues.error("decode", format!("invalid indexed sourcemap: {}", err));
+                None
+            }
+        },
+        Err(err) => {
+            issues.error("decode", format!("invalid sourcemap: {}", err));
+            None
+        }
+    }
+}
+
+/// Runs all checks on the given release files.
+pub fn validate_release_files(sources: &ReleaseFiles) -> ValidationReport {
+    let source_urls: HashSet<_> = sources.keys().cloned().collect();
+    let sourcemap_urls: HashSet<_> = sources
+        .values()
+        .filter(|s| s.ty == SourceFileType::SourceMap)
+        .map(|s| s.url.clone())
+        .collect();
+
+    let mut files: BTreeMap<&str, (Option<String>, Issues)> = BTreeMap::new();
+    let mut minified_files: BTreeMap<&str, &ReleaseFile> = BTreeMap::new();
+    for source in sources.values() {
+        if source.ty == SourceFileType::IndexedRamBundle {
+            continue;
+        }
+        let mut issues = Issues::default();
+        let mut sourcemap_url = None;
+        if source.ty != SourceFileType::SourceMap {
+            match find_sourcemap(sources, &sourcemap_urls, source) {
+                Ok(Some(sourcemap)) => {
+                    sourcemap_url = Some(sourcemap.url.clone());
+                    minified_files.insert(&sourcemap.url, source);
+                }
+                Ok(None) => {}
+                Err(err) => issues.error("sourcemap-reference", err.to_string()),
+            }
+        }
+        files.insert(&source.url, (sourcemap_url, issues));
+    }
+
+    for sourcemap in sources.values() {
+        if sourcemap.ty != SourceFileType::SourceMap {
+            continue;
+        }
+        let issues = &mut files.get_mut(sourcemap.url.as_str()).unwrap().1;
+        let sm = match decode_sourcemap(issues, sourcemap) {
+            Some(sm) => sm,
+            None => continue,
+        };
+        match minified_files.get(sourcemap.url.as_str()) {
+            Some(minified) => validate_minified_positions(issues, &sm, minified),
+            None => issues.warn(
+                "sourcemap-reference",
+                "not referenced by any minified file".into(),
+            ),
+        }
+        validate_sources(issues, &source_urls, &sm, sourcemap);
+    }
+
+    let mut report = ValidationReport::default();
+    for (url, (sourcemap, issues)) in files {
+        let issues = issues.finish();
+        for issue in &issues {
+            match issue.level {
+                LogLevel::Error => report.errors += 1,
+                LogLevel::Warning => report.warnings += 1,
+            }
+        }
+        report.files.push(FileReport {
+            url: url.to_string(),
+            path: sources[url].path.clone(),
+            sourcemap,
+            issues,
+        });
+    }
+    report
+}
+
+impl ValidationReport {
+    /// Returns whether any issue is at least as severe as the given level.
+    pub fn has_issues(&self, level: LogLevel) -> bool {
+        self.files
+            .iter()
+            .flat_map(|file| &file.issues)
+            .any(|issue| issue.level >= level)
+    }
+
+    /// Writes the report in the given format.
+    ///
+    /// In JUnit reports, issues below `fail_level` are not reported as failures.
+    pub fn write<W: Write>(
+        &self,
+        mut w: W,
+        format: ReportFormat,
+        fail_level: Option<LogLevel>,
+    ) -> Result<()> {
+        match format {
+            ReportFormat::Text => write!(w, "{}", self)?,
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(&mut w, self)?;
+                writeln!(w)?;
+            }
+            ReportFormat::Junit => {
+                self.to_junit(fail_level).to_writer(&mut w)?;
+                writeln!(w)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn to_junit(&self, fail_level: Option<LogLevel>) -> Element {
+        let is_failure = |level| matches!(fail_level, Some(fail_level) if level >= fail_level);
+        let failures = self
+            .files
+            .iter()
+            .filter(|file| file.issues.iter().any(|issue| is_failure(issue.level)))
+            .count();
+
+        let mut suites = Element::new("testsuites");
+        let suite = suites
+            .append_new_child("testsuite")
+            .set_attr("name", "sourcemaps")
+            .set_attr("tests", self.files.len().to_string())
+            .set_attr("failures", failures.to_string());
+        for file in &self.files {
+            let case = suite
+                .append_new_child("testcase")
+                .set_attr("classname", "sourcemaps")
+                .set_attr("name", &file.url);
+            let mut output = vec![];
+            for issue in &file.issues {
+                if is_failure(issue.level) {
+                    case.append_new_child("failure")
+                        .set_attr("type", issue.check)
+                        .set_attr("message", &issue.message);
+                } else {
+                    output.push(format!(
+                        "{} [{}]: {}",
+                        issue.level, issue.check, issue.message
+                    ));
+                }
+            }
+            if !output.is_empty() {
+                case.append_new_child("system-out")
+                    .set_text(output.join("\n"));
+            }
+        }
+        suites
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Source Map Validation Report")?;
+        for file in &self.files {
+            match file.sourcemap {
+                Some(ref sourcemap) => writeln!(f, "  {} (sourcemap {})", file.url, sourcemap)?,
+                None => writeln!(f, "  {}", file.url)?,
+            }
+            for issue in &file.issues {
+                writeln!(
+                    f,
+                    "    - {} [{}]: {}",
+                    issue.level, issue.check, issue.message
+                )?;
+            }
+        }
+        writeln!(
+            f,
+            "Found {} error(s) and {} warning(s) in {} file(s)",
+            self.errors,
+            self.warnings,
+            self.files.len()
+        )
+    }
+}
+
+#[test]
+fn test_count_banner_lines() {
+    let view = SourceView::new("/*! lib v1.0 */\nfoo();\n");
+    assert_eq!(count_banner_lines(&view), 1);
+    let view = SourceView::new("/**\n * lib v1.0\n */\n// MIT\nfoo();");
+    assert_eq!(count_banner_lines(&view), 4);
+    let view = SourceView::new("/*! lib v1.0 */foo();");
+    assert_eq!(count_banner_lines(&view), 0);
+    let view = SourceView::new("foo();\n//# sourceMappingURL=foo.js.map");
+    assert_eq!(count_banner_lines(&view), 0);
+}
+
+#[test]
+fn test_js_slice_from() {
+    assert_eq!(js_slice_from("foo(bar)", 4), Some("bar)"));
+    assert_eq!(js_slice_from("foo", 3), Some(""));
+    assert_eq!(js_slice_from("foo", 4), None);
+    assert_eq!(js_slice_from("\u{1f600}x", 2), Some("x"));
+}
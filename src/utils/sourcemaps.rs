@@ -1,23 +1,38 @@
 //! Provides sourcemap validation functionality.
 use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
+use std::fs::File;
 use std::mem;
-use std::path::PathBuf;
-use std::str;
+use std::path::{Path, PathBuf};
+use std::str::{self, FromStr};
 
-use anyhow::{bail, Error, Result};
+use anyhow::{bail, Context, Error, Result};
 use console::style;
-use indicatif::ProgressStyle;
+use indicatif::{HumanBytes, ProgressStyle};
 use log::{debug, info, warn};
-use sha1_smol::Digest;
+use serde_json::Value;
+use sha1_smol::{Digest, Sha1};
+use symbolic::common::DebugId;
 use symbolic::debuginfo::sourcebundle::SourceFileType;
 use url::Url;
 
 use crate::utils::enc::decode_unknown_string;
 use crate::utils::file_search::ReleaseFileMatch;
-use crate::utils::file_upload::{ReleaseFile, ReleaseFileUpload, ReleaseFiles, UploadContext};
+use crate::utils::file_upload::{
+    write_artifact_bundle, ReleaseFile, ReleaseFileUpload, ReleaseFiles, UploadContext,
+};
+use crate::utils::fs::get_sha1_checksum;
+use crate::utils::hermes::{
+    compose_hermes_sourcemap, get_function_offsets, get_function_offsets_value,
+    inject_function_offsets_into_sourcemap, is_hermes_bytecode, uncovered_functions,
+    HermesBytecodeHeader,
+};
 use crate::utils::logging::is_quiet_mode;
 use crate::utils::progress::ProgressBar;
+use crate::utils::sourcemap_compose::{compose_with_sourcemaps_on_disk, decode_flat_sourcemap};
+use crate::utils::sourcemap_optimize::{optimize_sourcemap, OptimizeOptions};
+use crate::utils::sourcemap_sources::SourceResolver;
+use crate::utils::sourcemap_validation::{validate_release_files, ValidationReport};
 
 fn is_likely_minified_js(code: &[u8]) -> bool {
     if let Ok(code_str) = decode_unknown_string(code) {
@@ -27,7 +42,7 @@ fn is_likely_minified_js(code: &[u8]) -> bool {
     }
 }
 
-fn join_url(base_url: &str, url: &str) -> Result<String> {
+pub fn join_url(base_url: &str, url: &str) -> Result<String> {
     if base_url.starts_with("~/") {
         match Url::parse(&format!("http://{}", base_url))?.join(url) {
             Ok(url) => {
@@ -85,6 +100,152 @@ pub fn get_sourcemap_ref(file: &ReleaseFile) -> Option<sourcemap::SourceMapRef>
     get_sourcemap_ref_from_headers(file).or_else(|| get_sourcemap_ref_from_contents(file))
 }
 
+/// Finds the sourcemap of a minified file, either by its reference or next
+/// to the file.
+pub fn find_sourcemap_path(path: &Path, contents: &[u8]) -> Option<PathBuf> {
+    if let Ok(Some(sm_ref)) = sourcemap::locate_sourcemap_reference_slice(contents) {
+        let url = sm_ref.get_url();
+        if url.starts_with("data:") {
+            debug!("{} has an inline sourcemap", path.display());
+            return None;
+        }
+        let url = url.split(&['?', '#'][..]).next().unwrap_or(url);
+        let candidate = path.parent().unwrap_or_else(|| Path::new("")).join(url);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        debug!(
+            "sourcemap reference {} of {} not found",
+            url,
+            path.display()
+        );
+    }
+
+    let mut candidate = path.as_os_str().to_owned();
+    candidate.push(".map");
+    let candidate = PathBuf::from(candidate);
+    candidate.is_file().then_some(candidate)
+}
+
+/// Resolves a source of a sourcemap to a file on disk, relative to the sourcemap.
+pub fn resolve_source_path(sourcemap_path: &Path, source: &str) -> Option<PathBuf> {
+    let path = match Url::parse(source) {
+        Ok(url) if url.scheme() == "file" => url.to_file_path().ok()?,
+        Ok(_) => return None,
+        Err(_) => sourcemap_path.parent()?.join(source),
+    };
+    path.is_file().then_some(path)
+}
+
+/// The comment that carries the debug ID of a minified file.
+const DEBUG_ID_COMMENT: &str = "//# debugId=";
+
+/// Registers the debug ID at runtime, keyed by the stack trace of the file it
+/// is part of, so that the SDK can attach it to events.
+const DEBUG_ID_SNIPPET: &str = "!function(){try{var e=\"undefined\"!=typeof window?window:\
+    \"undefined\"!=typeof global?global:\"undefined\"!=typeof self?self:{},n=(new e.Error).stack;\
+    n&&(e._sentryDebugIds=e._sentryDebugIds||{},e._sentryDebugIds[n]=\"__SENTRY_DEBUG_ID__\")}\
+    catch(e){}}();";
+
+/// Computes a deterministic debug ID for a minified file and its sourcemap.
+pub fn debug_id_for_pair(minified: &[u8], sourcemap: &[u8]) -> DebugId {
+    let mut sha = Sha1::new();
+    sha.update(minified);
+    sha.update(sourcemap);
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&sha.digest().bytes()[..16]);
+    DebugId::from_uuid(uuid::Builder::from_sha1_bytes(bytes).into_uuid())
+}
+
+/// Returns the debug ID from the trailing comments of a minified file.
+pub fn get_debug_id_from_minified(contents: &[u8]) -> Option<DebugId> {
+    for line in contents.split(|&b| b == b'\n').rev() {
+        let line = str::from_utf8(line).ok()?.trim();
+        if let Some(debug_id) = line.strip_prefix(DEBUG_ID_COMMENT) {
+            return DebugId::from_str(debug_id).ok();
+        }
+        if !line.is_empty() && !line.starts_with("//") {
+            break;
+        }
+    }
+    None
+}
+
+/// Returns the `debug_id` field of a sourcemap.
+pub fn get_debug_id_from_sourcemap(contents: &[u8]) -> Option<DebugId> {
+    let sourcemap: Value = serde_json::from_slice(contents).ok()?;
+    let debug_id = sourcemap
+        .get("debug_id")
+        .or_else(|| sourcemap.get("debugId"))?;
+    DebugId::from_str(debug_id.as_str()?).ok()
+}
+
+pub fn get_debug_id(file: &ReleaseFile) -> Option<DebugId> {
+    match file.ty {
+        SourceFileType::MinifiedSource => get_debug_id_from_minified(&file.contents),
+        SourceFileType::SourceMap => get_debug_id_from_sourcemap(&file.contents),
+        _ => None,
+    }
+}
+
+/// Appends the debug ID snippet and comment to a minified file.
+///
+/// Both are placed after the code so that existing mappings stay valid, but
+/// before a trailing `sourceMappingURL` comment.
+pub fn inject_debug_id_into_minified(contents: &[u8], debug_id: DebugId) -> Vec<u8> {
+    let end = contents
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(0, |pos| pos + 1);
+    let last_line = contents[..end]
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |pos| pos + 1);
+    let split = if contents[last_line..].starts_with(b"//# sourceMappingURL=")
+        || contents[last_line..].starts_with(b"//@ sourceMappingURL=")
+    {
+        last_line
+    } else {
+        contents.len()
+    };
+
+    let mut rv = contents[..split].to_vec();
+    if !rv.is_empty() && !rv.ends_with(b"\n") {
+        rv.push(b'\n');
+    }
+    let snippet = DEBUG_ID_SNIPPET.replace("__SENTRY_DEBUG_ID__", &debug_id.to_string());
+    rv.extend_from_slice(snippet.as_bytes());
+    rv.push(b'\n');
+    rv.extend_from_slice(format!("{}{}\n", DEBUG_ID_COMMENT, debug_id).as_bytes());
+    rv.extend_from_slice(&contents[split..]);
+    rv
+}
+
+/// Sets the `debug_id` field of a sourcemap.
+pub fn inject_debug_id_into_sourcemap(contents: &[u8], debug_id: DebugId) -> Result<Vec<u8>> {
+    let mut sourcemap: Value = serde_json::from_slice(contents)?;
+    match sourcemap.as_object_mut() {
+        Some(object) => {
+            object.remove("debugId");
+            object.insert("debug_id".into(), debug_id.to_string().into());
+        }
+        None => bail!("sourcemap is not a JSON object"),
+    }
+    Ok(serde_json::to_vec(&sourcemap)?)
+}
+
+/// Restores the fields of a sourcemap that the sourcemap crate drops when
+/// encoding it, the debug ID and the hermes function offsets.
+fn restore_dropped_fields(original: &[u8], mut contents: Vec<u8>) -> Result<Vec<u8>> {
+    if let Some(debug_id) = get_debug_id_from_sourcemap(original) {
+        contents = inject_debug_id_into_sourcemap(&contents, debug_id)?;
+    }
+    if let Some(offsets) = get_function_offsets_value(original) {
+        contents = inject_function_offsets_into_sourcemap(&contents, offsets)?;
+    }
+    Ok(contents)
+}
+
 pub fn get_sourcemap_reference_from_headers<'a, I: Iterator<Item = (&'a String, &'a String)>>(
     headers: I,
 ) -> Option<&'a str> {
@@ -97,7 +258,7 @@ pub fn get_sourcemap_reference_from_headers<'a, I: Iterator<Item = (&'a String,
     None
 }
 
-fn guess_sourcemap_reference(sourcemaps: &HashSet<String>, min_url: &str) -> Result<String> {
+pub fn guess_sourcemap_reference(sourcemaps: &HashSet<String>, min_url: &str) -> Result<String> {
     // if there is only one sourcemap in total we just assume that's the one.
     // We just need to make sure that we fix up the reference if we need to
     // (eg: ~/ -> /).
@@ -149,14 +310,9 @@ fn guess_sourcemap_reference(sourcemaps: &HashSet<String>, min_url: &str) -> Res
 pub struct SourceMapProcessor {
     pending_sources: HashSet<(String, ReleaseFileMatch)>,
     already_uploaded_sources: Vec<Digest>,
+    release_files: HashMap<String, Digest>,
     sources: ReleaseFiles,
-}
-
-fn is_hermes_bytecode(slice: &[u8]) -> bool {
-    // The hermes bytecode format magic is defined here:
-    // https://github.com/facebook/hermes/blob/5243222ef1d92b7393d00599fc5cff01d189a88a/include/hermes/BCGen/HBC/BytecodeFileFormat.h#L24-L25
-    const HERMES_MAGIC: [u8; 8] = [0xC6, 0x1F, 0xBC, 0x03, 0xC1, 0x03, 0x19, 0x1F];
-    slice.starts_with(&HERMES_MAGIC)
+    hermes_bundles: HashMap<String, HermesBytecodeHeader>,
 }
 
 impl SourceMapProcessor {
@@ -165,7 +321,9 @@ impl SourceMapProcessor {
         SourceMapProcessor {
             pending_sources: HashSet::new(),
             already_uploaded_sources: Vec::new(),
+            release_files: HashMap::new(),
             sources: HashMap::new(),
+            hermes_bundles: HashMap::new(),
         }
     }
 
@@ -180,6 +338,11 @@ impl SourceMapProcessor {
         self.already_uploaded_sources.push(checksum);
     }
 
+    /// Adds the URL and checksum of a file that is already in the release.
+    pub fn add_release_file(&mut self, url: &str, checksum: Digest) {
+        self.release_files.insert(url.to_string(), checksum);
+    }
+
     fn flush_pending_sources(&mut self) {
         if self.pending_sources.is_empty() {
             return;
@@ -192,11 +355,13 @@ impl SourceMapProcessor {
         let pb = ProgressBar::new(self.pending_sources.len());
         pb.set_style(progress_style);
 
-        println!(
-            "{} Analyzing {} sources",
-            style(">").dim(),
-            style(self.pending_sources.len()).yellow()
-        );
+        if !is_quiet_mode() {
+            println!(
+                "{} Analyzing {} sources",
+                style(">").dim(),
+                style(self.pending_sources.len()).yellow()
+            );
+        }
         for (url, mut file) in self.pending_sources.drain() {
             pb.set_message(&url);
 
@@ -211,6 +376,18 @@ impl SourceMapProcessor {
                 && sourcemap::ram_bundle::is_ram_bundle_slice(&file.contents)
             {
                 SourceFileType::IndexedRamBundle
+            } else if is_hermes_bytecode(&file.contents) {
+                // The bytecode is of no use for symbolication. It is uploaded as an
+                // empty minified file that carries the sourcemap reference, and its
+                // header is kept to link and validate the sourcemap.
+                match HermesBytecodeHeader::parse(&file.contents) {
+                    Ok(header) => {
+                        self.hermes_bundles.insert(url.clone(), header);
+                    }
+                    Err(err) => warn!("could not read hermes bytecode header of {}: {}", url, err),
+                }
+                file.contents.clear();
+                SourceFileType::MinifiedSource
             } else if file
                 .path
                 .file_name()
@@ -220,14 +397,6 @@ impl SourceMapProcessor {
                 || is_likely_minified_js(&file.contents)
             {
                 SourceFileType::MinifiedSource
-            } else if is_hermes_bytecode(&file.contents) {
-                // This is actually a big hack:
-                // For the react-native Hermes case, we skip uploading the bytecode bundle,
-                // and rather flag it as an empty "minified source". That way, it
-                // will get a SourceMap reference, and the server side processor
-                // should deal with it accordingly.
-                file.contents.clear();
-                SourceFileType::MinifiedSource
             } else {
                 SourceFileType::Source
             };
@@ -286,15 +455,24 @@ impl SourceMapProcessor {
                 continue;
             }
 
+            let debug_id = match get_debug_id(source) {
+                Some(debug_id) => format!(" (debug id {})", style(debug_id).dim()),
+                None => String::new(),
+            };
             if source.ty == SourceFileType::MinifiedSource {
                 if let Some(sm_ref) = get_sourcemap_ref(source) {
                     let url = sm_ref.get_url();
-                    println!("    {} (sourcemap at {})", &source.url, style(url).cyan());
+                    println!(
+                        "    {} (sourcemap at {}){}",
+                        &source.url,
+                        style(url).cyan(),
+                        debug_id
+                    );
                 } else {
-                    println!("    {} (no sourcemap ref)", &source.url);
+                    println!("    {} (no sourcemap ref){}", &source.url, debug_id);
                 }
             } else {
-                println!("    {}", &source.url);
+                println!("    {}{}", &source.url, debug_id);
             }
 
             for msg in source.messages.iter() {
@@ -348,6 +526,12 @@ impl SourceMapProcessor {
         bail!("Encountered problems when validating source maps.");
     }
 
+    /// Runs the deep validation checks on all sources and returns a report.
+    pub fn validation_report(&mut self) -> ValidationReport {
+        self.flush_pending_sources();
+        validate_release_files(&self.sources)
+    }
+
     /// Unpacks the given RAM bundle into a list of module sources and their sourcemaps
     pub fn unpack_ram_bundle(
         &mut self,
@@ -507,13 +691,383 @@ impl SourceMapProcessor {
                     .flatten_and_rewrite(&options)?
                     .to_writer(&mut new_source)?,
             };
-            source.contents = new_source;
+            source.contents = restore_dropped_fields(&source.contents, new_source)?;
             pb.inc(1);
         }
         pb.finish_with_duration("Rewriting");
         Ok(())
     }
 
+    /// Composes sourcemaps with the sourcemaps of their sources found on disk,
+    /// so that the output of multi-stage builds maps to the original sources.
+    pub fn compose(&mut self) -> Result<()> {
+        self.flush_pending_sources();
+
+        println!("{} Composing sourcemaps", style(">").dim());
+        for source in self.sources.values_mut() {
+            if source.ty != SourceFileType::SourceMap {
+                continue;
+            }
+            let sm = decode_flat_sourcemap(&source.contents)
+                .with_context(|| format!("Invalid sourcemap {}", source.url))?;
+            let composed = match compose_with_sourcemaps_on_disk(&sm, &source.path)? {
+                Some(composed) => composed,
+                None => continue,
+            };
+            let mut new_source: Vec<u8> = Vec::new();
+            composed.to_writer(&mut new_source)?;
+            info!("composed {} with earlier build stages", source.url);
+            source.contents = restore_dropped_fields(&source.contents, new_source)?;
+        }
+        Ok(())
+    }
+
+    /// Composes the packager sourcemap of a hermes bundle with the sourcemap
+    /// emitted by the hermes compiler.
+    pub fn compose_hermes_sourcemap(&mut self, sourcemap_url: &str, path: &Path) -> Result<()> {
+        self.flush_pending_sources();
+
+        let hermes_map =
+            std::fs::read(path).with_context(|| format!("Could not read {}", path.display()))?;
+        let source = match self.sources.get_mut(sourcemap_url) {
+            Some(source) if source.ty == SourceFileType::SourceMap => source,
+            _ => bail!("{} is not a sourcemap", sourcemap_url),
+        };
+        let composed = compose_hermes_sourcemap(&hermes_map, &source.contents)
+            .with_context(|| format!("Could not compose {} with {}", path.display(), source.url))?;
+        source.contents = match get_debug_id_from_sourcemap(&source.contents) {
+            Some(debug_id) => inject_debug_id_into_sourcemap(&composed, debug_id)?,
+            None => composed,
+        };
+        if !is_quiet_mode() {
+            println!(
+                "{} Composed {} with hermes sourcemap {}",
+                style(">").dim(),
+                sourcemap_url,
+                path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Links hermes bytecode bundles to their sourcemaps with a debug ID from
+    /// the bytecode header and checks that the sourcemaps cover all functions
+    /// of the bytecode.
+    ///
+    /// This runs whether or not sourcemap references were added.  Bundles
+    /// without a reference are matched to a sourcemap by name.
+    fn check_hermes_bundles(&mut self) -> Result<()> {
+        let mut bundles: Vec<_> = self.hermes_bundles.iter().collect();
+        bundles.sort_by_key(|(url, _)| url.as_str());
+        let sourcemaps: HashSet<_> = self
+            .sources
+            .values()
+            .filter(|x| x.ty == SourceFileType::SourceMap)
+            .map(|x| x.url.to_string())
+            .collect();
+
+        for (url, header) in bundles {
+            let bundle = match self.sources.get(url) {
+                Some(bundle) => bundle,
+                None => continue,
+            };
+            let sourcemap_ref = match get_sourcemap_ref_from_headers(bundle) {
+                Some(sm_ref) => Some(sm_ref.get_url().to_string()),
+                None => guess_sourcemap_reference(&sourcemaps, url).ok(),
+            };
+            let sourcemap_url = match sourcemap_ref {
+                Some(sm_ref) => Some(join_url(url, &sm_ref)?),
+                None => None,
+            };
+
+            // A debug ID already present in the sourcemap may have been
+            // embedded into the app, so it takes precedence.
+            let bytecode_id = header.debug_id();
+            let sourcemap_id = sourcemap_url
+                .as_ref()
+                .and_then(|sm_url| self.sources.get(sm_url))
+                .and_then(|sourcemap| get_debug_id_from_sourcemap(&sourcemap.contents));
+            let debug_id = sourcemap_id.unwrap_or(bytecode_id);
+
+            let bundle = self.sources.get_mut(url).unwrap();
+            if debug_id != bytecode_id {
+                bundle.warn(format!(
+                    "sourcemap has debug id {}, but the hermes bytecode has {}",
+                    debug_id, bytecode_id
+                ));
+            }
+            bundle
+                .headers
+                .push(("debug-id".to_string(), debug_id.to_string()));
+            let sourcemap_url = match sourcemap_url {
+                Some(sourcemap_url) => sourcemap_url,
+                None => continue,
+            };
+            let sourcemap = match self.sources.get_mut(&sourcemap_url) {
+                Some(sourcemap) => sourcemap,
+                None => continue,
+            };
+
+            if get_debug_id_from_sourcemap(&sourcemap.contents).is_none() {
+                sourcemap.contents = inject_debug_id_into_sourcemap(&sourcemap.contents, debug_id)?;
+            }
+
+            let offsets = match get_function_offsets(&sourcemap.contents) {
+                Some(offsets) => offsets,
+                None => {
+                    sourcemap.warn(
+                        "hermes sourcemap has no function offsets, it was likely not composed \
+                         with the sourcemap of the hermes compiler"
+                            .into(),
+                    );
+                    continue;
+                }
+            };
+            let function_count: usize = offsets.values().map(Vec::len).sum();
+            if function_count != header.function_count as usize {
+                sourcemap.warn(format!(
+                    "sourcemap has {} functions, but the hermes bytecode of {} has {}",
+                    function_count, url, header.function_count
+                ));
+            }
+            let sm = decode_flat_sourcemap(&sourcemap.contents)?;
+            let uncovered = uncovered_functions(&sm, &offsets);
+            if let Some((segment, offset)) = uncovered.first() {
+                sourcemap.warn(format!(
+                    "{} of {} bytecode functions have no mappings, the first at \
+                     segment {} offset {}",
+                    uncovered.len(),
+                    function_count,
+                    segment,
+                    offset
+                ));
+            }
+
+            if !is_quiet_mode() {
+                println!(
+                    "{} Hermes bytecode {} (version {}): {} of {} functions mapped",
+                    style(">").dim(),
+                    url,
+                    header.version,
+                    style(function_count - uncovered.len()).yellow(),
+                    function_count
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Reduces the size of all sourcemaps and prints the size saved per file.
+    ///
+    /// With deduplication, sources with the same URL and content in several
+    /// sourcemaps are uploaded once as a separate file instead of being
+    /// embedded in every sourcemap.
+    pub fn optimize(&mut self, options: &OptimizeOptions) -> Result<()> {
+        self.flush_pending_sources();
+
+        if !is_quiet_mode() {
+            println!("{} Optimizing sourcemaps", style(">").dim());
+        }
+
+        let mut optimized = vec![];
+        for source in self.sources.values() {
+            if source.ty != SourceFileType::SourceMap {
+                continue;
+            }
+            let sm = match sourcemap::decode_slice(&source.contents)? {
+                sourcemap::DecodedMap::Regular(sm) => sm,
+                sourcemap::DecodedMap::Index(smi) => smi.flatten()?,
+                // Re-encoding would drop the function metadata of hermes maps.
+                sourcemap::DecodedMap::Hermes(_) => continue,
+            };
+            optimized.push((source.url.clone(), optimize_sourcemap(&sm, options)));
+        }
+
+        let mut shared_sources = HashMap::new();
+        if options.dedupes_sources_content() {
+            let mut occurrences: HashMap<(String, &str), usize> = HashMap::new();
+            for (url, sm) in &optimized {
+                for idx in 0..sm.get_source_count() {
+                    if let (Some(name), Some(contents)) =
+                        (sm.get_source(idx), sm.get_source_contents(idx))
+                    {
+                        *occurrences
+                            .entry((join_url(url, name)?, contents))
+                            .or_default() += 1;
+                    }
+                }
+            }
+            for ((source_url, contents), count) in occurrences {
+                if count > 1 {
+                    shared_sources.insert(source_url, contents.to_string());
+                }
+            }
+            // A file uploaded under the same URL or already in the release takes
+            // precedence, so only contents that match it can be removed from the
+            // sourcemaps.
+            let release_files = &self.release_files;
+            shared_sources.retain(|url, contents| match self.sources.get(url) {
+                Some(existing) => existing.contents == contents.as_bytes(),
+                None => match release_files.get(url) {
+                    Some(checksum) => {
+                        matches!(get_sha1_checksum(contents.as_bytes()), Ok(c) if c == *checksum)
+                    }
+                    None => true,
+                },
+            });
+        }
+
+        let mut report = vec![];
+        for (url, mut sm) in optimized {
+            for idx in 0..sm.get_source_count() {
+                let is_shared = match (sm.get_source(idx), sm.get_source_contents(idx)) {
+                    (Some(name), Some(contents)) => {
+                        shared_sources
+                            .get(&join_url(&url, name)?)
+                            .map(String::as_str)
+                            == Some(contents)
+                    }
+                    _ => false,
+                };
+                if is_shared {
+                    sm.set_source_contents(idx, None);
+                }
+            }
+
+            let source = self.sources.get_mut(&url).unwrap();
+            let mut new_source: Vec<u8> = Vec::new();
+            sm.to_writer(&mut new_source)?;
+            let new_source = restore_dropped_fields(&source.contents, new_source)?;
+            report.push((url, source.contents.len(), new_source.len()));
+            source.contents = new_source;
+        }
+
+        let shared_count = shared_sources.len();
+        let shared_size: usize = shared_sources.values().map(String::len).sum();
+        for (url, contents) in shared_sources {
+            if self.sources.contains_key(&url) {
+                continue;
+            }
+            self.sources.insert(
+                url.clone(),
+                ReleaseFile {
+                    url,
+                    path: PathBuf::new(),
+                    contents: contents.into_bytes(),
+                    ty: SourceFileType::Source,
+                    headers: vec![],
+                    messages: vec![],
+                    already_uploaded: false,
+                },
+            );
+        }
+
+        if is_quiet_mode() {
+            return Ok(());
+        }
+        report.sort();
+        for (url, before, after) in &report {
+            let saved = before.saturating_sub(*after);
+            println!(
+                "    {}: {} -> {} (saved {}%)",
+                url,
+                HumanBytes(*before as u64),
+                HumanBytes(*after as u64),
+                if *before > 0 { saved * 100 / before } else { 0 }
+            );
+        }
+        if shared_count > 0 {
+            println!(
+                "{} Moved {} shared source{} ({}) out of the sourcemaps",
+                style(">").dim(),
+                style(shared_count).yellow(),
+                if shared_count == 1 { "" } else { "s" },
+                HumanBytes(shared_size as u64)
+            );
+        }
+        let before: usize = report.iter().map(|(_, before, _)| before).sum();
+        let after: usize = report.iter().map(|(_, _, after)| after).sum();
+        println!(
+            "{} Saved {} in {} sourcemap{}",
+            style(">").dim(),
+            style(HumanBytes(before.saturating_sub(after) as u64)).yellow(),
+            report.len(),
+            if report.len() == 1 { "" } else { "s" }
+        );
+        Ok(())
+    }
+
+    /// Inlines sources that are missing from `sourcesContent` and prints a
+    /// summary of the sources that could not be found.
+    pub fn inline_sources(&mut self, resolver: &SourceResolver) -> Result<()> {
+        self.flush_pending_sources();
+
+        let mut inlined = 0;
+        let mut from_git = 0;
+        let mut missing = vec![];
+        for source in self.sources.values_mut() {
+            if source.ty != SourceFileType::SourceMap {
+                continue;
+            }
+            let mut sm = match sourcemap::decode_slice(&source.contents)? {
+                sourcemap::DecodedMap::Regular(sm) => sm,
+                sourcemap::DecodedMap::Index(smi) => smi.flatten()?,
+                // Re-encoding would drop the function metadata of hermes maps.
+                sourcemap::DecodedMap::Hermes(_) => continue,
+            };
+            let result = resolver.inline_sources(&mut sm, &source.path);
+            inlined += result.inlined();
+            from_git += result.from_git;
+            missing.extend(
+                result
+                    .missing
+                    .iter()
+                    .map(|name| (source.url.clone(), name.clone())),
+            );
+            if result.inlined() == 0 {
+                continue;
+            }
+
+            let mut new_source: Vec<u8> = Vec::new();
+            sm.to_writer(&mut new_source)?;
+            source.contents = restore_dropped_fields(&source.contents, new_source)?;
+        }
+
+        if is_quiet_mode() {
+            return Ok(());
+        }
+        if inlined > 0 {
+            print!(
+                "{} Inlined {} source{}",
+                style(">").dim(),
+                style(inlined).yellow(),
+                if inlined == 1 { "" } else { "s" }
+            );
+            if from_git > 0 {
+                print!(" ({} from git)", from_git);
+            }
+            println!();
+        }
+        if !missing.is_empty() {
+            missing.sort();
+            println!(
+                "{} Could not inline {} source{}:",
+                style(">").dim(),
+                style(missing.len()).yellow(),
+                if missing.len() == 1 { "" } else { "s" }
+            );
+            let mut last_url = None;
+            for (url, name) in &missing {
+                if last_url != Some(url) {
+                    println!("    {}", url);
+                    last_url = Some(url);
+                }
+                println!("      {}", style(name).red());
+            }
+        }
+        Ok(())
+    }
+
     /// Adds sourcemap references to all minified files
     pub fn add_sourcemap_references(&mut self) -> Result<()> {
         self.flush_pending_sources();
@@ -560,6 +1114,7 @@ impl SourceMapProcessor {
     /// Uploads all files
     pub fn upload(&mut self, context: &UploadContext<'_>) -> Result<()> {
         self.flush_pending_sources();
+        self.check_hermes_bundles()?;
         self.flag_uploaded_sources();
         let mut uploader = ReleaseFileUpload::new(context);
         uploader.files(&self.sources);
@@ -567,6 +1122,17 @@ impl SourceMapProcessor {
         self.dump_log("Source Map Upload Report");
         Ok(())
     }
+
+    /// Writes all files into an artifact bundle instead of uploading them.
+    pub fn write_bundle(&mut self, context: &UploadContext<'_>, path: &Path) -> Result<()> {
+        self.flush_pending_sources();
+        self.check_hermes_bundles()?;
+        let file =
+            File::create(path).with_context(|| format!("Could not create {}", path.display()))?;
+        write_artifact_bundle(context, &self.sources, file)?;
+        self.dump_log("Artifact Bundle Report");
+        Ok(())
+    }
 }
 
 fn validate_script(source: &mut ReleaseFile) -> Result<()> {
@@ -616,6 +1182,30 @@ impl Default for SourceMapProcessor {
     }
 }
 
+#[test]
+fn test_inject_debug_id() {
+    let debug_id = debug_id_for_pair(b"foo();", b"{}");
+    assert_eq!(debug_id, debug_id_for_pair(b"foo();", b"{}"));
+    assert_ne!(debug_id, debug_id_for_pair(b"bar();", b"{}"));
+
+    let minified =
+        inject_debug_id_into_minified(b"foo();\n//# sourceMappingURL=foo.js.map\n", debug_id);
+    let minified = str::from_utf8(&minified).unwrap();
+    assert!(minified.starts_with("foo();\n!function(){"));
+    assert!(minified.ends_with(&format!(
+        "}}();\n//# debugId={}\n//# sourceMappingURL=foo.js.map\n",
+        debug_id
+    )));
+    assert_eq!(
+        get_debug_id_from_minified(minified.as_bytes()),
+        Some(debug_id)
+    );
+    assert_eq!(get_debug_id_from_minified(b"foo();"), None);
+
+    let sourcemap = inject_debug_id_into_sourcemap(br#"{"version":3}"#, debug_id).unwrap();
+    assert_eq!(get_debug_id_from_sourcemap(&sourcemap), Some(debug_id));
+}
+
 #[test]
 fn test_split_url() {
     assert_eq!(split_url("/foo.js"), (Some(""), "foo", Some("js")));
@@ -0,0 +1,246 @@
+//! Keeps envelopes that could not be sent on disk so they can be sent later.
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, format_err, Context, Result};
+use backoff::backoff::Backoff;
+use clap::ArgMatches;
+use console::style;
+use log::debug;
+use sentry::protocol::Event;
+use sentry::types::Dsn;
+use sentry::{Envelope, Transport};
+use uuid::Uuid;
+
+use crate::api::{Api, EnvelopeResponse};
+use crate::config::Config;
+use crate::utils::event::{serialize_envelope, with_sentry_transport};
+use crate::utils::retry::get_default_backoff;
+
+/// The file extension of spooled envelopes.
+const SPOOL_EXT: &str = "envelope";
+
+/// The subdirectory envelopes are moved to if Sentry rejects them.
+const REJECTED_DIR: &str = "rejected";
+
+/// Where an envelope ended up after trying to send it.
+pub enum Dispatched {
+    /// The envelope was accepted by Sentry.
+    Sent,
+    /// The envelope could not be sent and was written to the spool.
+    Spooled(PathBuf),
+}
+
+/// Returns the spool directory passed with `--spool-dir` or configured.
+pub fn get_spool_dir(matches: &ArgMatches) -> Option<PathBuf> {
+    matches
+        .value_of("spool_dir")
+        .map(PathBuf::from)
+        .or_else(|| Config::current().get_spool_dir())
+}
+
+/// Writes a serialized envelope into the spool directory.
+///
+/// File names start with the time of spooling so that envelopes are sent in
+/// the order they were spooled in.
+pub fn spool_envelope(dir: &Path, envelope: &[u8]) -> Result<PathBuf> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Could not create spool directory {}", dir.display()))?;
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let name = format!("{:013}-{}", millis, Uuid::new_v4().simple());
+    let path = dir.join(&name).with_extension(SPOOL_EXT);
+
+    // write to a temporary file first so that a concurrent flush never
+    // picks up a partially written envelope.
+    let temp_path = dir.join(&name).with_extension("tmp");
+    fs::write(&temp_path, envelope)
+        .with_context(|| format!("Could not write {}", temp_path.display()))?;
+    fs::rename(&temp_path, &path)?;
+
+    Ok(path)
+}
+
+/// Sends an envelope, spooling it if sending fails in a way that might
+/// succeed later.
+pub fn send_or_spool(dsn: &Dsn, envelope: &[u8], spool_dir: &Path) -> Result<Dispatched> {
+    match Api::current().send_envelope(dsn, envelope) {
+        Ok(EnvelopeResponse::Accepted) => return Ok(Dispatched::Sent),
+        Ok(response) if !response.is_retryable() => {
+            bail!("Sentry rejected the envelope ({})", describe(response))
+        }
+        Ok(response) => debug!("could not send envelope: {}", describe(response)),
+        Err(err) => debug!("could not send envelope: {}", err),
+    }
+    Ok(Dispatched::Spooled(spool_envelope(spool_dir, envelope)?))
+}
+
+/// A transport that sends envelopes right away and spools them if sending
+/// fails, remembering where each envelope ended up.
+struct SpoolTransport {
+    dsn: Dsn,
+    dir: PathBuf,
+    dispatched: Mutex<Vec<Result<Dispatched>>>,
+}
+
+impl Transport for SpoolTransport {
+    fn send_envelope(&self, envelope: Envelope) {
+        let rv = serialize_envelope(&envelope)
+            .and_then(|envelope| send_or_spool(&self.dsn, &envelope, &self.dir));
+        self.dispatched.lock().unwrap().push(rv);
+    }
+}
+
+/// Captures an event with a regular sentry client and sends it, spooling it
+/// if sending fails in a way that might succeed later.
+pub fn capture_or_spool(event: Event<'static>, dsn: Dsn, dir: &Path) -> Result<(Uuid, Dispatched)> {
+    let transport = Arc::new(SpoolTransport {
+        dsn: dsn.clone(),
+        dir: dir.to_path_buf(),
+        dispatched: Mutex::new(vec![]),
+    });
+    let id = with_sentry_transport(dsn, Arc::new(transport.clone()), |c| {
+        c.capture_event(event, None)
+    });
+    let dispatched = transport.dispatched.lock().unwrap().pop();
+    match dispatched {
+        Some(dispatched) => Ok((id, dispatched?)),
+        None => Err(format_err!("The event was discarded before sending")),
+    }
+}
+
+/// Returns the paths of all spooled envelopes in the order they were spooled.
+fn list_spooled_envelopes(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut rv = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension() == Some(OsStr::new(SPOOL_EXT)) {
+            rv.push(path);
+        }
+    }
+    rv.sort();
+    Ok(rv)
+}
+
+fn describe(response: EnvelopeResponse) -> String {
+    match response {
+        EnvelopeResponse::Accepted => "accepted".into(),
+        EnvelopeResponse::RateLimited(seconds) => {
+            format!("rate limited, retry in {} seconds", seconds)
+        }
+        EnvelopeResponse::Failed(status) => format!("status {}", status),
+    }
+}
+
+/// The outcome of sending a single spooled envelope.
+enum FlushOutcome {
+    Sent,
+    Rejected(EnvelopeResponse),
+    RateLimited(u64),
+    Failed(String),
+}
+
+/// Sends a single envelope, retrying network and server errors with backoff.
+fn flush_envelope(api: &Api, dsn: &Dsn, envelope: &[u8], max_retries: u32) -> FlushOutcome {
+    let mut backoff = get_default_backoff();
+    let mut retry_number = 0;
+
+    loop {
+        let error = match api.send_envelope(dsn, envelope) {
+            Ok(EnvelopeResponse::Accepted) => return FlushOutcome::Sent,
+            Ok(EnvelopeResponse::RateLimited(seconds)) => {
+                return FlushOutcome::RateLimited(seconds)
+            }
+            Ok(response) if !response.is_retryable() => return FlushOutcome::Rejected(response),
+            Ok(response) => describe(response),
+            Err(err) => format!("{:#}", anyhow::Error::from(err)),
+        };
+
+        if retry_number >= max_retries {
+            return FlushOutcome::Failed(error);
+        }
+        let timeout = backoff.next_backoff().unwrap();
+        debug!("retry number {}: {}", retry_number, error);
+        thread::sleep(timeout);
+        retry_number += 1;
+    }
+}
+
+/// Sends all envelopes in the spool directory and prints the outcome for
+/// each of them.
+///
+/// Sent envelopes are removed from the spool and envelopes Sentry rejects
+/// are moved to the `rejected` subdirectory.  Once Sentry starts rate
+/// limiting, the remaining envelopes are kept for the next flush.
+pub fn flush_spool(dsn: &Dsn, dir: &Path) -> Result<()> {
+    let api = Api::current();
+    let max_retries = Config::current().get_max_retry_count()?;
+    let paths = list_spooled_envelopes(dir)?;
+
+    if paths.is_empty() {
+        println!("No spooled envelopes in {}", dir.display());
+        return Ok(());
+    }
+
+    let mut sent = 0;
+    let mut rate_limited = false;
+    for path in &paths {
+        let name = path.file_name().unwrap().to_string_lossy();
+
+        if rate_limited {
+            println!("  {} {}", style("deferred").dim(), name);
+            continue;
+        }
+
+        let envelope = fs::read(path)?;
+        if let Err(err) = Envelope::from_slice(&envelope) {
+            println!("  {} {} ({})", style("invalid").red(), name, err);
+            continue;
+        }
+
+        match flush_envelope(&api, dsn, &envelope, max_retries) {
+            FlushOutcome::Sent => {
+                fs::remove_file(path)?;
+                sent += 1;
+                println!("  {} {}", style("sent").green(), name);
+            }
+            FlushOutcome::Rejected(response) => {
+                let rejected_dir = dir.join(REJECTED_DIR);
+                fs::create_dir_all(&rejected_dir)?;
+                fs::rename(path, rejected_dir.join(path.file_name().unwrap()))?;
+                println!(
+                    "  {} {} ({}, moved to {})",
+                    style("rejected").red(),
+                    name,
+                    describe(response),
+                    REJECTED_DIR
+                );
+            }
+            FlushOutcome::RateLimited(seconds) => {
+                rate_limited = true;
+                println!(
+                    "  {} {} (retry in {} seconds)",
+                    style("rate limited").yellow(),
+                    name,
+                    seconds
+                );
+            }
+            FlushOutcome::Failed(err) => {
+                println!("  {} {} ({})", style("failed").red(), name, err);
+            }
+        }
+    }
+
+    println!("Sent {} of {} spooled envelope(s).", sent, paths.len());
+    Ok(())
+}
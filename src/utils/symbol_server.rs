@@ -0,0 +1,213 @@
+//! A minimal HTTP server for debug information files in local directories.
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+use anyhow::Result;
+use log::{debug, warn};
+use percent_encoding::percent_decode_str;
+use symbolic::common::ByteView;
+use symbolic::debuginfo::{Archive, FileFormat};
+use walkdir::WalkDir;
+
+use crate::utils::dif_layout::{layout_paths, DifLayout};
+
+/// A debug information file, or a slice of a fat archive, served for a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    pub path: PathBuf,
+    pub offset: u64,
+    pub len: u64,
+}
+
+/// Maps the paths of all symbol server layouts to the files found in
+/// directories, regardless of the layout of the directories themselves.
+#[derive(Debug, Default)]
+pub struct SymbolIndex {
+    entries: HashMap<String, IndexEntry>,
+    files: usize,
+}
+
+impl SymbolIndex {
+    /// Indexes all debug information files in the given directories.
+    pub fn build<I, P>(paths: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut index = SymbolIndex::default();
+        for base in paths {
+            for entry in WalkDir::new(base.as_ref())
+                .follow_links(true)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_file())
+            {
+                index.add_file(entry.path())?;
+            }
+        }
+        Ok(index)
+    }
+
+    fn add_file(&mut self, path: &Path) -> Result<()> {
+        let buffer = ByteView::open(path)?;
+        if Archive::peek(&buffer) == FileFormat::Unknown {
+            return Ok(());
+        }
+        let archive = match Archive::parse(&buffer) {
+            Ok(archive) => archive,
+            Err(err) => {
+                warn!("Skipping invalid debug file {}: {}", path.display(), err);
+                return Ok(());
+            }
+        };
+
+        let file_name = path.to_string_lossy();
+        let mut indexed = false;
+        for object in archive.objects().filter_map(Result::ok) {
+            let data = object.data();
+            let entry = IndexEntry {
+                path: path.to_path_buf(),
+                offset: (data.as_ptr() as usize - buffer.as_ptr() as usize) as u64,
+                len: data.len() as u64,
+            };
+            for layout in DifLayout::all() {
+                for layout_path in layout_paths(layout, &object, &file_name) {
+                    debug!("serving {} as {}", path.display(), layout_path);
+                    self.entries
+                        .insert(layout_path.to_lowercase(), entry.clone());
+                    indexed = true;
+                }
+            }
+        }
+        if indexed {
+            self.files += 1;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of indexed files.
+    pub fn files(&self) -> usize {
+        self.files
+    }
+
+    /// Looks up a path in any layout.  Symbol servers are case insensitive.
+    pub fn lookup(&self, path: &str) -> Option<&IndexEntry> {
+        self.entries
+            .get(&path.trim_start_matches('/').to_lowercase())
+    }
+}
+
+/// Returns the method and the decoded path of an HTTP request line.
+fn parse_request_line(line: &str) -> Option<(&str, String)> {
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    parts
+        .next()
+        .filter(|version| version.starts_with("HTTP/"))?;
+    let path = target.split(&['?', '#'][..]).next().unwrap_or_default();
+    let path = percent_decode_str(path).decode_utf8().ok()?;
+    Some((method, path.into_owned()))
+}
+
+fn respond(stream: &mut TcpStream, status: &str, len: u64) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status, len
+    )
+}
+
+fn handle_request(mut stream: TcpStream, index: &SymbolIndex) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the headers, none of them change the response.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let (method, path) = match parse_request_line(&request_line) {
+        Some(request) => request,
+        None => return respond(&mut stream, "400 Bad Request", 0),
+    };
+    if method != "GET" && method != "HEAD" {
+        return respond(&mut stream, "405 Method Not Allowed", 0);
+    }
+
+    let entry = match index.lookup(&path) {
+        Some(entry) => entry,
+        None => {
+            println!("{} {} 404", method, path);
+            return respond(&mut stream, "404 Not Found", 0);
+        }
+    };
+    println!("{} {} 200 ({})", method, path, entry.path.display());
+    respond(&mut stream, "200 OK", entry.len)?;
+    if method == "GET" {
+        let mut file = File::open(&entry.path)?;
+        file.seek(SeekFrom::Start(entry.offset))?;
+        io::copy(&mut file.take(entry.len), &mut stream)?;
+    }
+    Ok(())
+}
+
+/// Serves the indexed files until the process is terminated.
+pub fn serve(listener: TcpListener, index: SymbolIndex) -> Result<()> {
+    let index = Arc::new(index);
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let index = index.clone();
+        thread::spawn(move || {
+            if let Err(err) = handle_request(stream, &index) {
+                debug!("failed to handle request: {}", err);
+            }
+        });
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parse_request_line() {
+    assert_eq!(
+        parse_request_line("GET /buildid/abc/debuginfo?x=1 HTTP/1.1\r\n"),
+        Some(("GET", "/buildid/abc/debuginfo".to_string()))
+    );
+    assert_eq!(
+        parse_request_line("HEAD /My%20App/ID/My%20App HTTP/1.0"),
+        Some(("HEAD", "/My App/ID/My App".to_string()))
+    );
+    assert_eq!(parse_request_line("GET /"), None);
+}
+
+#[test]
+fn test_serve() {
+    let index = SymbolIndex::build(["tests/integration/_fixtures/elf-Linux-ARMv7-ls"]).unwrap();
+    assert_eq!(index.files(), 1);
+    let path = "/BuildID/02547a308094c28e25f1a4adc744a9917194db0a/executable";
+    assert!(index.lookup(path).is_some());
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || serve(listener, index));
+
+    let get = |path: &str| {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = vec![];
+        stream.read_to_end(&mut response).unwrap();
+        response
+    };
+
+    let expected = std::fs::read("tests/integration/_fixtures/elf-Linux-ARMv7-ls").unwrap();
+    let response = get(path);
+    assert!(response.starts_with(b"HTTP/1.1 200 OK\r\n"));
+    assert!(response.ends_with(&expected));
+    assert!(get("/buildid/0000/executable").starts_with(b"HTTP/1.1 404 Not Found\r\n"));
+}
@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::env;
 use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use anyhow::{Error, Result};
 use console::style;
@@ -11,6 +12,17 @@ use crate::config::Config;
 #[cfg(not(windows))]
 use crate::utils::xcode::launched_from_xcode;
 
+/// Set when the running command handles SIGINT and SIGTERM itself.
+static INTERRUPTS_DEFERRED: AtomicBool = AtomicBool::new(false);
+
+/// Stops `run_or_interrupt` from exiting the process on SIGINT and SIGTERM.
+///
+/// Commands calling this are responsible for handling these signals
+/// themselves, e.g. to clean up or forward them to a child process.
+pub fn defer_interrupts() {
+    INTERRUPTS_DEFERRED.store(true, Ordering::SeqCst);
+}
+
 #[cfg(not(windows))]
 pub fn run_or_interrupt<F>(f: F)
 where
@@ -43,10 +55,14 @@ where
         }
     });
 
-    if let Ok(signal) = rx.recv() {
+    while let Ok(signal) = rx.recv() {
+        if signal != 0 && INTERRUPTS_DEFERRED.load(Ordering::SeqCst) {
+            continue;
+        }
         if signal == signal_hook::consts::SIGINT {
             eprintln!("Interrupted!");
         }
+        break;
     }
 }
 
@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, format_err, Result};
+use sentry::protocol::{Context, Span, SpanId, SpanStatus, TraceContext, TraceId, Transaction};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::utils::args::get_timestamp;
+use crate::utils::event::get_sdk_info;
+
+/// A point in time given either as unix timestamp or as a date string.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Timestamp {
+    Seconds(f64),
+    Text(String),
+}
+
+impl Timestamp {
+    fn to_system_time(&self) -> Result<SystemTime> {
+        match self {
+            Timestamp::Seconds(secs) => add_seconds(UNIX_EPOCH, *secs, "timestamp"),
+            Timestamp::Text(text) => Ok(get_timestamp(text)?.into()),
+        }
+    }
+}
+
+/// A span as declared in a transaction description.
+///
+/// A span starts at `start_timestamp` or `offset` seconds after its parent
+/// started, and ends at `timestamp` or after `duration` seconds.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpanDescription {
+    pub op: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<SpanStatus>,
+    pub start_timestamp: Option<Timestamp>,
+    pub timestamp: Option<Timestamp>,
+    pub offset: Option<f64>,
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    #[serde(default)]
+    pub data: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub spans: Vec<SpanDescription>,
+}
+
+/// A transaction with its child spans, as read by `send-transaction`.
+///
+/// The transaction ends at `timestamp`, after `duration` seconds, or now.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionDescription {
+    pub name: String,
+    pub op: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<SpanStatus>,
+    pub release: Option<String>,
+    pub environment: Option<String>,
+    pub start_timestamp: Option<Timestamp>,
+    pub timestamp: Option<Timestamp>,
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    #[serde(default)]
+    pub spans: Vec<SpanDescription>,
+}
+
+fn seconds(value: f64, what: &str) -> Result<Duration> {
+    if !value.is_finite() || value < 0.0 {
+        bail!("{} must be a positive number of seconds", what);
+    }
+    Duration::try_from_secs_f64(value).map_err(|_| format_err!("{} {} is too large", what, value))
+}
+
+fn add_seconds(time: SystemTime, value: f64, what: &str) -> Result<SystemTime> {
+    time.checked_add(seconds(value, what)?)
+        .ok_or_else(|| format_err!("{} {} is too large", what, value))
+}
+
+fn sub_seconds(time: SystemTime, value: f64, what: &str) -> Result<SystemTime> {
+    time.checked_sub(seconds(value, what)?)
+        .ok_or_else(|| format_err!("{} {} is too large", what, value))
+}
+
+/// Creates a transaction with a new trace that covers the given time range.
+pub fn new_transaction(
+    name: &str,
+    op: &str,
+    start: SystemTime,
+    end: SystemTime,
+    status: SpanStatus,
+) -> Transaction<'static> {
+    let mut transaction = Transaction {
+        name: Some(name.to_string()),
+        sdk: Some(get_sdk_info()),
+        start_timestamp: start,
+        timestamp: Some(end),
+        ..Default::default()
+    };
+    transaction.contexts.insert(
+        "trace".into(),
+        TraceContext {
+            op: Some(op.to_string()),
+            status: Some(status),
+            ..Default::default()
+        }
+        .into(),
+    );
+    transaction
+}
+
+/// Returns the trace context of a transaction created by `new_transaction`.
+fn trace_context(transaction: &Transaction<'_>) -> (TraceId, SpanId) {
+    match transaction.contexts.get("trace") {
+        Some(Context::Trace(trace)) => (trace.trace_id, trace.span_id),
+        _ => unreachable!("transaction without trace context"),
+    }
+}
+
+/// Adds a span and all of its children to `spans`.
+fn add_span(
+    spans: &mut Vec<Span>,
+    span: SpanDescription,
+    trace_id: TraceId,
+    parent_span_id: SpanId,
+    parent_start: SystemTime,
+) -> Result<()> {
+    let name = span
+        .description
+        .as_deref()
+        .or(span.op.as_deref())
+        .unwrap_or("unnamed span")
+        .to_string();
+
+    let start = match (&span.start_timestamp, span.offset) {
+        (Some(ts), None) => ts.to_system_time()?,
+        (None, Some(offset)) => add_seconds(parent_start, offset, "offset")?,
+        (None, None) => parent_start,
+        (Some(_), Some(_)) => bail!(
+            "span {}: only one of start_timestamp and offset may be given",
+            name
+        ),
+    };
+    let end = match (&span.timestamp, span.duration) {
+        (Some(ts), None) => ts.to_system_time()?,
+        (None, Some(duration)) => add_seconds(start, duration, "duration")?,
+        (None, None) => bail!("span {}: either timestamp or duration is required", name),
+        (Some(_), Some(_)) => bail!(
+            "span {}: only one of timestamp and duration may be given",
+            name
+        ),
+    };
+    if end < start {
+        bail!("span {} ends before it starts", name);
+    }
+
+    let span_id = SpanId::default();
+    spans.push(Span {
+        span_id,
+        trace_id,
+        parent_span_id: Some(parent_span_id),
+        op: span.op,
+        description: span.description,
+        start_timestamp: start,
+        timestamp: Some(end),
+        status: span.status,
+        tags: span.tags.into_iter().collect(),
+        data: span.data.into_iter().collect(),
+        ..Default::default()
+    });
+
+    for child in span.spans {
+        add_span(spans, child, trace_id, span_id, start)?;
+    }
+    Ok(())
+}
+
+impl TransactionDescription {
+    /// Builds the transaction and flattens the nested spans.
+    pub fn into_transaction(self) -> Result<Transaction<'static>> {
+        let end = match (&self.timestamp, &self.start_timestamp, self.duration) {
+            (Some(ts), _, None) => ts.to_system_time()?,
+            (None, Some(start), Some(duration)) => {
+                add_seconds(start.to_system_time()?, duration, "duration")?
+            }
+            (None, _, _) => SystemTime::now(),
+            (Some(_), _, Some(_)) => bail!("only one of timestamp and duration may be given"),
+        };
+        let start = match (&self.start_timestamp, self.duration) {
+            (Some(ts), _) => ts.to_system_time()?,
+            (None, Some(duration)) => sub_seconds(end, duration, "duration")?,
+            (None, None) => bail!("either start_timestamp or duration is required"),
+        };
+        if end < start {
+            bail!("transaction ends before it starts");
+        }
+
+        let mut transaction = new_transaction(
+            &self.name,
+            self.op.as_deref().unwrap_or("default"),
+            start,
+            end,
+            self.status.unwrap_or(SpanStatus::Ok),
+        );
+        if let Some(Context::Trace(ref mut trace)) = transaction.contexts.get_mut("trace") {
+            trace.description = self.description;
+        }
+        transaction.release = self.release.map(Into::into);
+        transaction.environment = self.environment.map(Into::into);
+        transaction.tags = self.tags.into_iter().collect();
+
+        let (trace_id, span_id) = trace_context(&transaction);
+        for span in self.spans {
+            add_span(&mut transaction.spans, span, trace_id, span_id, start)?;
+        }
+        Ok(transaction)
+    }
+}
+
+#[test]
+fn test_transaction_from_description() {
+    let description: TransactionDescription = serde_json::from_value(serde_json::json!({
+        "name": "build",
+        "op": "ci.build",
+        "start_timestamp": 1000,
+        "duration": 60,
+        "spans": [
+            {
+                "op": "compile",
+                "offset": 5,
+                "duration": 30,
+                "spans": [{"op": "link", "offset": 20, "duration": 10}]
+            },
+            {"op": "test", "start_timestamp": "1970-01-01T00:17:20Z", "timestamp": 1055}
+        ]
+    }))
+    .unwrap();
+
+    let transaction = description.into_transaction().unwrap();
+    let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+    assert_eq!(transaction.start_timestamp, at(1000));
+    assert_eq!(transaction.timestamp, Some(at(1060)));
+
+    let (trace_id, span_id) = trace_context(&transaction);
+    let spans = &transaction.spans;
+    assert_eq!(spans.len(), 3);
+    assert_eq!(spans[0].start_timestamp, at(1005));
+    assert_eq!(spans[0].parent_span_id, Some(span_id));
+    assert_eq!(spans[1].op.as_deref(), Some("link"));
+    assert_eq!(spans[1].start_timestamp, at(1025));
+    assert_eq!(spans[1].timestamp, Some(at(1035)));
+    assert_eq!(spans[1].parent_span_id, Some(spans[0].span_id));
+    assert_eq!(spans[2].start_timestamp, at(1040));
+    assert!(spans.iter().all(|span| span.trace_id == trace_id));
+}
+
+#[test]
+fn test_transaction_out_of_range() {
+    let description: TransactionDescription = serde_json::from_value(serde_json::json!({
+        "name": "build",
+        "start_timestamp": 1e300,
+        "duration": 60,
+    }))
+    .unwrap();
+    assert!(description.into_transaction().is_err());
+
+    let description: TransactionDescription = serde_json::from_value(serde_json::json!({
+        "name": "build",
+        "start_timestamp": 1000,
+        "duration": 60,
+        "spans": [{"op": "compile", "offset": 1e19, "duration": 1e19}]
+    }))
+    .unwrap();
+    assert!(description.into_transaction().is_err());
+}
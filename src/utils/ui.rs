@@ -3,14 +3,18 @@ use std::io::{Read, Write};
 
 use crate::utils::progress::{ProgressBar, ProgressStyle};
 
-/// Prints a message and loops until yes or no is entered.
+/// Prints a message and loops until yes or no is entered.  Answers no if
+/// the input is closed.
 pub fn prompt_to_continue(message: &str) -> io::Result<bool> {
     loop {
         print!("{} [y/n] ", message);
         io::stdout().flush()?;
 
         let mut buf = String::new();
-        io::stdin().read_line(&mut buf)?;
+        if io::stdin().read_line(&mut buf)? == 0 {
+            println!();
+            return Ok(false);
+        }
         let input = buf.trim();
 
         if input == "y" {
@@ -0,0 +1,5 @@
+function greet(name: string): string {
+  return "Hello, " + name;
+}
+
+throw new Error(greet("world"));
@@ -0,0 +1,2 @@
+function greet(n){return"Hello, "+n}throw new Error(greet("world"));
+//# sourceMappingURL=greet.min.js.map
@@ -0,0 +1,2 @@
+function add(a,b){return a+b}
+//# sourceMappingURL=main-9e1b.js.map
@@ -0,0 +1,2 @@
+function add(a,b){return a+b}
+//# sourceMappingURL=index-4f2a1c.js.map
@@ -0,0 +1,2 @@
+lib(b());
+//# sourceMappingURL=b.js.map
@@ -0,0 +1,2 @@
+lib(a());
+//# sourceMappingURL=a.js.map
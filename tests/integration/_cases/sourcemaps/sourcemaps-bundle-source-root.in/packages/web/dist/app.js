@@ -0,0 +1,2 @@
+a();b();c()
+//# sourceMappingURL=app.js.map
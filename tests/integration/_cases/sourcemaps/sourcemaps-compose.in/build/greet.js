@@ -0,0 +1,5 @@
+function greet(name) {
+    return "Hello, " + name;
+}
+throw new Error(greet("world"));
+//# sourceMappingURL=greet.js.map
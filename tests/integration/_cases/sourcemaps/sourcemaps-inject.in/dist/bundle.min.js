@@ -0,0 +1,2 @@
+!function(n){throw new Error(n)}("whoops");
+//# sourceMappingURL=bundle.min.js.map
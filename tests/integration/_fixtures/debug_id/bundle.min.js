@@ -0,0 +1,4 @@
+!function(n){throw new Error(n)}("whoops");
+!function(){try{var e="undefined"!=typeof window?window:"undefined"!=typeof global?global:"undefined"!=typeof self?self:{},n=(new e.Error).stack;n&&(e._sentryDebugIds=e._sentryDebugIds||{},e._sentryDebugIds[n]="0b62b7fc-e2fd-5611-8d77-934c819841f5")}catch(e){}}();
+//# debugId=0b62b7fc-e2fd-5611-8d77-934c819841f5
+//# sourceMappingURL=bundle.min.js.map
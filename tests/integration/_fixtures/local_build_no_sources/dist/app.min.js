@@ -0,0 +1,2 @@
+function o(n){r(n)}function r(n){throw new Error(n)}o("whoops");
+//# sourceMappingURL=app.min.js.map
@@ -0,0 +1,3 @@
+/*! app v1.0.0 | MIT */
+function o(n){r(n)}function r(n){throw new Error(n)}o("whoops");
+//# sourceMappingURL=app.min.js.map
@@ -0,0 +1,2 @@
+function l(n){return n+1}
+//# sourceMappingURL=lib.min.js.map
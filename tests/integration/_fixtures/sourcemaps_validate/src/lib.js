@@ -0,0 +1,3 @@
+function inc(value) {
+  return value + 2;
+}
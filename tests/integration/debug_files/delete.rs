@@ -0,0 +1,19 @@
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+
+#[test]
+fn command_debug_files_delete() {
+    let _list = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/files/dsyms/?cursor=&query=5f81d6be-c5a2-3f8a-9e1b-ea2cf5a12b34",
+            200,
+        )
+        .with_response_file("debug_files/get-difs.json"),
+    );
+    let _delete = mock_endpoint(EndpointOptions::new(
+        "DELETE",
+        "/api/0/projects/wat-org/wat-project/files/dsyms/?id=203166512",
+        204,
+    ));
+    register_test("debug_files/debug_files-delete.trycmd");
+}
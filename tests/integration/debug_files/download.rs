@@ -0,0 +1,27 @@
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+
+#[test]
+fn command_debug_files_download() {
+    let _list = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/files/dsyms/?cursor=",
+            200,
+        )
+        .with_response_file("debug_files/get-difs.json"),
+    );
+    let _download = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/files/dsyms/?id=203166440",
+            200,
+        )
+        .with_response_body("fake elf contents\n"),
+    );
+    register_test("debug_files/debug_files-download.trycmd");
+}
+
+#[test]
+fn command_debug_files_download_no_selection() {
+    register_test("debug_files/debug_files-download-no-selection.trycmd");
+}
@@ -0,0 +1,16 @@
+use crate::integration::register_test;
+
+#[test]
+fn command_debug_files_export_help() {
+    register_test("debug_files/debug_files-export-help.trycmd");
+}
+
+#[test]
+fn command_debug_files_export() {
+    register_test("debug_files/debug_files-export.trycmd");
+}
+
+#[test]
+fn command_debug_files_export_in_place() {
+    register_test("debug_files/debug_files-export-in-place.trycmd");
+}
@@ -0,0 +1,40 @@
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+
+#[test]
+fn command_debug_files_list() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/files/dsyms/?cursor=",
+            200,
+        )
+        .with_response_file("debug_files/get-difs.json"),
+    );
+    register_test("debug_files/debug_files-list.trycmd");
+}
+
+#[test]
+fn command_debug_files_list_filtered() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/files/dsyms/?cursor=&file_formats=macho&file_formats=pdb",
+            200,
+        )
+        .with_response_file("debug_files/get-difs.json"),
+    );
+    register_test("debug_files/debug_files-list-filtered.trycmd");
+}
+
+#[test]
+fn command_debug_files_list_empty() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/files/dsyms/?cursor=",
+            200,
+        )
+        .with_response_file("debug_files/get-difs.json"),
+    );
+    register_test("debug_files/debug_files-list-empty.trycmd");
+}
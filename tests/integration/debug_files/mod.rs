@@ -1,6 +1,12 @@
 use crate::integration::register_test;
 
 mod check;
+mod delete;
+mod download;
+mod export;
+mod list;
+mod prune;
+mod serve;
 mod upload;
 
 #[test]
@@ -0,0 +1,85 @@
+use mockito::mock;
+
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+
+#[test]
+fn command_debug_files_prune_dry_run() {
+    let _releases = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/releases/?cursor=",
+            200,
+        )
+        .with_response_file("debug_files/get-releases.json"),
+    );
+    let _list = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/files/dsyms/?cursor=",
+            200,
+        )
+        .with_response_file("debug_files/get-difs.json"),
+    );
+    register_test("debug_files/debug_files-prune-dry-run.trycmd");
+}
+
+#[test]
+fn command_debug_files_prune() {
+    let _list = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/files/dsyms/?cursor=&file_formats=elf",
+            200,
+        )
+        .with_response_file("debug_files/get-difs.json"),
+    );
+    let _delete = mock_endpoint(EndpointOptions::new(
+        "DELETE",
+        "/api/0/projects/wat-org/wat-project/files/dsyms/?id=203166440",
+        204,
+    ));
+    register_test("debug_files/debug_files-prune.trycmd");
+}
+
+#[test]
+fn command_debug_files_prune_no_policy() {
+    register_test("debug_files/debug_files-prune-no-policy.trycmd");
+}
+
+#[test]
+fn command_debug_files_prune_paginated_releases() {
+    let first_page = mock(
+        "GET",
+        "/api/0/projects/wat-org/wat-project/releases/?cursor=",
+    )
+    .with_header("content-type", "application/json")
+    .with_header(
+        "link",
+        "<http://localhost/>; rel=\"next\"; results=\"true\"; cursor=\"100:1:0\"",
+    )
+    .with_body("[]")
+    .create();
+    let second_page = mock(
+        "GET",
+        "/api/0/projects/wat-org/wat-project/releases/?cursor=100:1:0",
+    )
+    .with_header("content-type", "application/json")
+    .with_body_from_file("tests/integration/_responses/debug_files/get-releases.json")
+    .create();
+    let _list = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/files/dsyms/?cursor=&file_formats=pdb",
+            200,
+        )
+        .with_response_body("[]"),
+    );
+    register_test("debug_files/debug_files-prune-paginated-releases.trycmd");
+    first_page.assert();
+    second_page.assert();
+}
+
+#[test]
+fn command_debug_files_prune_keep_no_releases() {
+    register_test("debug_files/debug_files-prune-keep-no-releases.trycmd");
+}
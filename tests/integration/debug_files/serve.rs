@@ -0,0 +1,6 @@
+use crate::integration::register_test;
+
+#[test]
+fn command_debug_files_serve_help() {
+    register_test("debug_files/debug_files-serve-help.trycmd");
+}
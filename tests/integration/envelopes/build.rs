@@ -0,0 +1,16 @@
+use crate::integration::register_test;
+
+#[test]
+fn command_envelopes_build() {
+    register_test("envelopes/envelopes-build.trycmd");
+}
+
+#[test]
+fn command_envelopes_build_two_events() {
+    register_test("envelopes/envelopes-build-two-events.trycmd");
+}
+
+#[test]
+fn command_envelopes_build_invalid_event() {
+    register_test("envelopes/envelopes-build-invalid-event.trycmd");
+}
@@ -0,0 +1,16 @@
+use crate::integration::register_test;
+
+#[test]
+fn command_envelopes_inspect() {
+    register_test("envelopes/envelopes-inspect.trycmd");
+}
+
+#[test]
+fn command_envelopes_inspect_invalid() {
+    register_test("envelopes/envelopes-inspect-invalid.trycmd");
+}
+
+#[test]
+fn command_envelopes_inspect_huge_length() {
+    register_test("envelopes/envelopes-inspect-huge-length.trycmd");
+}
@@ -0,0 +1,9 @@
+use crate::integration::register_test;
+
+mod build;
+mod inspect;
+
+#[test]
+fn command_envelopes_help() {
+    register_test("envelopes/envelopes-help.trycmd");
+}
@@ -0,0 +1,22 @@
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+use mockito::Matcher;
+use serde_json::json;
+
+#[test]
+fn command_issues_assign() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "PUT",
+            "/api/0/projects/wat-org/wat-project/issues/?id=1&id=2",
+            200,
+        )
+        .with_response_body("{}")
+        .with_matcher(Matcher::Json(json!({ "assignedTo": "team:42" }))),
+    );
+    register_test("issues/issues-assign.trycmd");
+}
+
+#[test]
+fn command_issues_assign_help() {
+    register_test("issues/issues-assign-help.trycmd");
+}
@@ -0,0 +1,19 @@
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+
+#[test]
+fn command_issues_list_help() {
+    register_test("issues/issues-list-help.trycmd");
+}
+
+#[test]
+fn command_issues_list_json() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/issues/?cursor=&query=is:unresolved%20release:1.0%2Bbuild",
+            200,
+        )
+        .with_response_file("issues/get-issues.json"),
+    );
+    register_test("issues/issues-list-json.trycmd");
+}
@@ -0,0 +1,11 @@
+use crate::integration::register_test;
+
+#[test]
+fn command_issues_merge_requires_two_issues() {
+    register_test("issues/issues-merge-single.trycmd");
+}
+
+#[test]
+fn command_issues_merge_rejects_all() {
+    register_test("issues/issues-merge-all.trycmd");
+}
@@ -0,0 +1,17 @@
+use crate::integration::register_test;
+
+mod assign;
+mod list;
+mod merge;
+mod mute;
+mod resolve;
+
+#[test]
+fn command_issues_help() {
+    register_test("issues/issues-help.trycmd");
+}
+
+#[test]
+fn command_issues_no_subcommand() {
+    register_test("issues/issues-no-subcommand.trycmd");
+}
@@ -0,0 +1,28 @@
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+use mockito::Matcher;
+use serde_json::json;
+
+#[test]
+fn command_issues_mute_until_count() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "PUT",
+            "/api/0/projects/wat-org/wat-project/issues/?status=unresolved",
+            200,
+        )
+        .with_response_body("{}")
+        .with_matcher(Matcher::Json(json!({
+            "status": "muted",
+            "statusDetails": {
+                "ignoreCount": 100,
+                "ignoreWindow": 60
+            }
+        }))),
+    );
+    register_test("issues/issues-mute-until-count.trycmd");
+}
+
+#[test]
+fn command_issues_mute_window_requires_threshold() {
+    register_test("issues/issues-mute-window-requires-threshold.trycmd");
+}
@@ -0,0 +1,63 @@
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+
+#[test]
+fn command_issues_resolve_query() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "PUT",
+            "/api/0/projects/wat-org/wat-project/issues/?query=is:unresolved%20release:1.0",
+            200,
+        )
+        .with_response_body("{}"),
+    );
+    register_test("issues/issues-resolve-query.trycmd");
+}
+
+#[test]
+fn command_issues_resolve_dry_run() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/issues/?cursor=&query=is:unresolved%20release:1.0",
+            200,
+        )
+        .with_response_body("[]"),
+    );
+    register_test("issues/issues-resolve-dry-run.trycmd");
+}
+
+#[test]
+fn command_issues_resolve_status_dry_run() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/issues/?cursor=&query=is:unresolved",
+            200,
+        )
+        .with_response_file("issues/get-issues.json"),
+    );
+    register_test("issues/issues-resolve-status-dry-run.trycmd");
+}
+
+#[test]
+fn command_issues_resolve_ids_dry_run() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/issues/?cursor=&query=&id=3337485411&id=3337485412",
+            200,
+        )
+        .with_response_file("issues/get-issues.json"),
+    );
+    register_test("issues/issues-resolve-ids-dry-run.trycmd");
+}
+
+#[test]
+fn command_issues_resolve_help() {
+    register_test("issues/issues-resolve-help.trycmd");
+}
+
+#[test]
+fn command_issues_resolve_conflicting_selectors() {
+    register_test("issues/issues-resolve-conflicting-selectors.trycmd");
+}
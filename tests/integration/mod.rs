@@ -1,8 +1,10 @@
 mod bash_hook;
 mod debug_files;
 mod deploys;
+mod envelopes;
 mod help;
 mod info;
+mod issues;
 mod login;
 mod monitors;
 mod organizations;
@@ -10,6 +12,8 @@ mod projects;
 mod releases;
 mod send_envelope;
 mod send_event;
+mod send_session;
+mod send_transaction;
 mod sourcemaps;
 mod uninstall;
 mod update;
@@ -36,6 +40,15 @@ pub fn register_test(path: &str) -> TestCases {
     test_case.insert_var("[VERSION]", VERSION).unwrap();
     test_case
 }
+
+/// Returns a DSN pointing at the mock server for the given project.
+pub fn mock_dsn(project: u32) -> String {
+    format!(
+        "http://test@{}/{}",
+        server_url().trim_start_matches("http://"),
+        project
+    )
+}
 pub struct EndpointOptions {
     pub method: String,
     pub endpoint: String,
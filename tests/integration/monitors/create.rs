@@ -0,0 +1,43 @@
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+use mockito::Matcher;
+use serde_json::json;
+
+#[test]
+fn command_monitors_create() {
+    let _server = mock_endpoint(
+        EndpointOptions::new("POST", "/api/0/organizations/wat-org/monitors/", 201)
+            .with_response_file("monitors/get-monitor.json")
+            .with_matcher(Matcher::Json(json!({
+                "name": "foo-monitor",
+                "type": "cron_job",
+                "config": {
+                    "schedule_type": "crontab",
+                    "schedule": "0 * * * *",
+                    "checkin_margin": 5,
+                    "max_runtime": 30,
+                    "timezone": "Europe/Vienna"
+                }
+            }))),
+    );
+    register_test("monitors/monitors-create.trycmd");
+}
+
+#[test]
+fn command_monitors_create_interval() {
+    let _server = mock_endpoint(
+        EndpointOptions::new("POST", "/api/0/organizations/wat-org/monitors/", 201)
+            .with_response_file("monitors/get-monitor.json")
+            .with_matcher(Matcher::PartialJson(json!({
+                "config": {
+                    "schedule_type": "interval",
+                    "schedule": [10, "minute"]
+                }
+            }))),
+    );
+    register_test("monitors/monitors-create-interval.trycmd");
+}
+
+#[test]
+fn command_monitors_create_requires_schedule() {
+    register_test("monitors/monitors-create-no-schedule.trycmd");
+}
@@ -0,0 +1,14 @@
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+
+#[test]
+fn command_monitors_delete() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "DELETE",
+            "/api/0/organizations/wat-org/monitors/foo-monitor/",
+            204,
+        )
+        .with_response_body(""),
+    );
+    register_test("monitors/monitors-delete.trycmd");
+}
@@ -0,0 +1,14 @@
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+
+#[test]
+fn command_monitors_info() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/organizations/wat-org/monitors/foo-monitor/",
+            200,
+        )
+        .with_response_file("monitors/get-monitor.json"),
+    );
+    register_test("monitors/monitors-info.trycmd");
+}
@@ -1,7 +1,12 @@
 use crate::integration::register_test;
 
+mod create;
+mod delete;
+mod info;
 mod list;
 mod run;
+mod sync;
+mod update;
 
 #[test]
 fn command_monitors_help() {
@@ -1,4 +1,6 @@
-use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+use crate::integration::{mock_dsn, mock_endpoint, register_test, EndpointOptions};
+use mockito::Matcher;
+use serde_json::json;
 
 #[test]
 fn command_monitors_run() {
@@ -14,8 +16,143 @@ fn command_monitors_run() {
 }
 
 #[test]
-fn command_monitors_run_invalid_uuid() {
-    register_test("monitors/monitors-run-invalid-uuid.trycmd");
+fn command_monitors_run_slug() {
+    let _monitor = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/organizations/wat-org/monitors/foo-monitor/",
+            200,
+        )
+        .with_response_file("monitors/get-monitor.json"),
+    );
+    let _checkin = mock_endpoint(
+        EndpointOptions::new(
+            "POST",
+            "/api/0/monitors/85a34e5a-c0b6-11ec-9d64-0242ac120002/checkins/",
+            200,
+        )
+        .with_response_file("monitors/post-monitors.json"),
+    );
+    register_test("monitors/monitors-run-slug.trycmd");
+}
+
+#[test]
+fn command_monitors_run_missing_slug() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/organizations/wat-org/monitors/missing-monitor/",
+            404,
+        )
+        .with_response_body(r#"{"detail": "The requested resource does not exist"}"#),
+    );
+    register_test("monitors/monitors-run-missing-slug.trycmd");
+}
+
+#[test]
+fn command_monitors_run_create_slug() {
+    let _monitor = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/organizations/wat-org/monitors/foo-monitor/",
+            404,
+        )
+        .with_response_body(r#"{"detail": "The requested resource does not exist"}"#),
+    );
+    let _create = mock_endpoint(
+        EndpointOptions::new("POST", "/api/0/organizations/wat-org/monitors/", 201)
+            .with_response_file("monitors/get-monitor.json")
+            .with_matcher(Matcher::PartialJson(json!({
+                "name": "foo-monitor",
+                "slug": "foo-monitor",
+                "config": {
+                    "schedule": "0 * * * *"
+                }
+            }))),
+    );
+    let _checkin = mock_endpoint(
+        EndpointOptions::new(
+            "POST",
+            "/api/0/monitors/85a34e5a-c0b6-11ec-9d64-0242ac120002/checkins/",
+            200,
+        )
+        .with_response_file("monitors/post-monitors.json"),
+    );
+    register_test("monitors/monitors-run-create-slug.trycmd");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn command_monitors_run_capture_output() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "POST",
+            "/api/0/monitors/85a34e5a-c0b6-11ec-9d64-0242ac120002/checkins/",
+            200,
+        )
+        .with_response_file("monitors/post-monitors.json"),
+    );
+    let envelope = mock_endpoint(
+        EndpointOptions::new("POST", "/api/1337/envelope/", 200)
+            .with_response_body("{}")
+            .with_matcher(Matcher::AllOf(vec![
+                Matcher::Regex(r#""exit_code":"3""#.into()),
+                Matcher::Regex(r#""stdout":"out\\n""#.into()),
+                Matcher::Regex(r#""stderr":"err\\n""#.into()),
+            ])),
+    );
+    register_test("monitors/monitors-run-capture-output.trycmd").env("SENTRY_DSN", mock_dsn(1337));
+    envelope.assert();
+}
+
+#[test]
+fn command_monitors_run_id_with_config() {
+    register_test("monitors/monitors-run-id-with-config.trycmd");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn command_monitors_run_timeout() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "POST",
+            "/api/0/monitors/85a34e5a-c0b6-11ec-9d64-0242ac120002/checkins/",
+            200,
+        )
+        .with_response_file("monitors/post-monitors.json"),
+    );
+    let _update = mock_endpoint(
+        EndpointOptions::new(
+            "PUT",
+            "/api/0/monitors/85a34e5a-c0b6-11ec-9d64-0242ac120002/checkins/85a34e5a-c0b6-11ec-9d64-0242ac120002/",
+            200,
+        )
+        .with_response_file("monitors/post-monitors.json")
+        .with_matcher(Matcher::PartialJson(json!({ "status": "timeout" }))),
+    );
+    register_test("monitors/monitors-run-timeout.trycmd");
+}
+
+#[test]
+fn command_monitors_run_transaction() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "POST",
+            "/api/0/monitors/85a34e5a-c0b6-11ec-9d64-0242ac120002/checkins/",
+            200,
+        )
+        .with_response_file("monitors/post-monitors.json"),
+    );
+    let envelope = mock_endpoint(
+        EndpointOptions::new("POST", "/api/1337/envelope/", 200)
+            .with_response_body("{}")
+            .with_matcher(Matcher::AllOf(vec![
+                Matcher::Regex(r#""type":"transaction""#.into()),
+                Matcher::Regex(r#""monitor":"85a34e5a-c0b6-11ec-9d64-0242ac120002""#.into()),
+            ])),
+    );
+    register_test("monitors/monitors-run-transaction.trycmd").env("SENTRY_DSN", mock_dsn(1337));
+    envelope.assert();
 }
 
 #[test]
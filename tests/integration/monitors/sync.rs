@@ -0,0 +1,43 @@
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+use mockito::Matcher;
+use serde_json::json;
+
+#[test]
+fn command_monitors_sync_dry_run() {
+    let _server = mock_endpoint(
+        EndpointOptions::new("GET", "/api/0/organizations/wat-org/monitors/?cursor=", 200)
+            .with_response_file("monitors/get-monitors-with-config.json"),
+    );
+    register_test("monitors/monitors-sync-dry-run.trycmd");
+}
+
+#[test]
+fn command_monitors_sync_reset() {
+    let _monitors = mock_endpoint(
+        EndpointOptions::new("GET", "/api/0/organizations/wat-org/monitors/?cursor=", 200)
+            .with_response_file("monitors/get-monitors-with-margin.json"),
+    );
+    let update = mock_endpoint(
+        EndpointOptions::new(
+            "PUT",
+            "/api/0/organizations/wat-org/monitors/85a34e5a-c0b6-11ec-9d64-0242ac120002/",
+            200,
+        )
+        .with_response_file("monitors/get-monitor.json")
+        .with_matcher(Matcher::PartialJson(json!({
+            "config": {
+                "schedule": "0 * * * *",
+                "checkin_margin": 1,
+                "max_runtime": 30,
+                "timezone": "UTC"
+            }
+        }))),
+    );
+    register_test("monitors/monitors-sync-reset.trycmd");
+    update.assert();
+}
+
+#[test]
+fn command_monitors_sync_help() {
+    register_test("monitors/monitors-sync-help.trycmd");
+}
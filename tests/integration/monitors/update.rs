@@ -0,0 +1,21 @@
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+use mockito::Matcher;
+use serde_json::json;
+
+#[test]
+fn command_monitors_update() {
+    let _server = mock_endpoint(
+        EndpointOptions::new(
+            "PUT",
+            "/api/0/organizations/wat-org/monitors/foo-monitor/",
+            200,
+        )
+        .with_response_file("monitors/get-monitor.json")
+        .with_matcher(Matcher::Json(json!({
+            "config": {
+                "max_runtime": 30
+            }
+        }))),
+    );
+    register_test("monitors/monitors-update.trycmd");
+}
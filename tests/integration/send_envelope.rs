@@ -1,4 +1,9 @@
-use crate::integration::register_test;
+use std::fs;
+
+use mockito::mock;
+use tempfile::tempdir;
+
+use crate::integration::{mock_dsn, mock_endpoint, register_test, EndpointOptions};
 
 #[test]
 fn command_send_envelope_help() {
@@ -19,3 +24,55 @@ fn command_send_envelope_file() {
 fn command_send_envelope_with_logging() {
     register_test("send_envelope/send_envelope-file-log.trycmd");
 }
+
+#[test]
+fn command_send_envelope_spool() {
+    let spool_dir = tempdir().unwrap();
+    let _server = mock_endpoint(EndpointOptions::new("POST", "/api/1/envelope/", 503));
+    register_test("send_envelope/send_envelope-spool.trycmd")
+        .env("SENTRY_DSN", mock_dsn(1))
+        .env("SENTRY_SPOOL_DIR", spool_dir.path().to_str().unwrap());
+    assert_eq!(fs::read_dir(spool_dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn command_send_envelope_flush_spool() {
+    let spool_dir = tempdir().unwrap();
+    fs::copy(
+        "tests/integration/_fixtures/envelope.dat",
+        spool_dir.path().join("0000000000001-test.envelope"),
+    )
+    .unwrap();
+    let _server = mock_endpoint(
+        EndpointOptions::new("POST", "/api/2/envelope/", 200).with_response_body("{}"),
+    );
+    register_test("send_envelope/send_envelope-flush-spool.trycmd")
+        .env("SENTRY_DSN", mock_dsn(2))
+        .env("SENTRY_SPOOL_DIR", spool_dir.path().to_str().unwrap());
+    assert_eq!(fs::read_dir(spool_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn command_send_envelope_flush_spool_rate_limited() {
+    let spool_dir = tempdir().unwrap();
+    for name in &["0000000000001-test.envelope", "0000000000002-test.envelope"] {
+        fs::copy(
+            "tests/integration/_fixtures/envelope.dat",
+            spool_dir.path().join(name),
+        )
+        .unwrap();
+    }
+    let _server = mock("POST", "/api/3/envelope/")
+        .with_status(429)
+        .with_header("x-sentry-rate-limits", "120:error:organization")
+        .create();
+    register_test("send_envelope/send_envelope-flush-spool-rate-limited.trycmd")
+        .env("SENTRY_DSN", mock_dsn(3))
+        .env("SENTRY_SPOOL_DIR", spool_dir.path().to_str().unwrap());
+    assert_eq!(fs::read_dir(spool_dir.path()).unwrap().count(), 2);
+}
+
+#[test]
+fn command_send_envelope_flush_spool_not_configured() {
+    register_test("send_envelope/send_envelope-flush-spool-not-configured.trycmd");
+}
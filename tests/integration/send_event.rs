@@ -1,4 +1,8 @@
-use crate::integration::register_test;
+use std::fs;
+
+use tempfile::tempdir;
+
+use crate::integration::{mock_dsn, mock_endpoint, register_test, EndpointOptions};
 
 #[test]
 fn command_send_event_help() {
@@ -18,3 +22,29 @@ fn command_send_event_raw() {
 fn command_send_event_file() {
     register_test("send_event/send_event-file.trycmd");
 }
+
+#[test]
+fn command_send_event_spool() {
+    let spool_dir = tempdir().unwrap();
+    let _server = mock_endpoint(EndpointOptions::new("POST", "/api/6/envelope/", 503));
+    register_test("send_event/send_event-spool.trycmd")
+        .env("SENTRY_DSN", mock_dsn(6))
+        .env("SENTRY_ENVIRONMENT", "staging")
+        .env("SENTRY_SPOOL_DIR", spool_dir.path().to_str().unwrap());
+
+    // The event is processed by the regular client before it is spooled.
+    let spooled: Vec<_> = fs::read_dir(spool_dir.path()).unwrap().collect();
+    assert_eq!(spooled.len(), 1);
+    let envelope = fs::read_to_string(spooled[0].as_ref().unwrap().path()).unwrap();
+    assert!(envelope.contains(r#""environment":"staging""#));
+}
+
+#[test]
+fn command_send_event_spool_rejected() {
+    let spool_dir = tempdir().unwrap();
+    let _server = mock_endpoint(EndpointOptions::new("POST", "/api/7/envelope/", 400));
+    register_test("send_event/send_event-spool-rejected.trycmd")
+        .env("SENTRY_DSN", mock_dsn(7))
+        .env("SENTRY_SPOOL_DIR", spool_dir.path().to_str().unwrap());
+    assert_eq!(fs::read_dir(spool_dir.path()).unwrap().count(), 0);
+}
@@ -0,0 +1,52 @@
+use mockito::{server_url, Matcher};
+use tempfile::tempdir;
+
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+
+#[test]
+fn command_send_session_help() {
+    register_test("send_session/send_session-help.trycmd");
+}
+
+#[test]
+fn command_send_session() {
+    register_test("send_session/send_session.trycmd");
+}
+
+#[test]
+fn command_send_session_aggregate() {
+    register_test("send_session/send_session-aggregate.trycmd");
+}
+
+#[test]
+fn command_send_session_conflicting_args() {
+    register_test("send_session/send_session-conflicting-args.trycmd");
+}
+
+#[test]
+fn command_send_session_invalid_duration() {
+    register_test("send_session/send_session-invalid-duration.trycmd");
+}
+
+#[test]
+fn command_send_session_direct() {
+    let spool_dir = tempdir().unwrap();
+    let _server = mock_endpoint(
+        EndpointOptions::new("POST", "/api/4/envelope/", 200)
+            .with_response_body("{}")
+            .with_matcher(Matcher::AllOf(vec![
+                Matcher::Regex(r#""did":"kiosk-1""#.into()),
+                Matcher::Regex(r#""status":"crashed""#.into()),
+                Matcher::Regex(r#""attrs":\{"release":"1.0.0","environment":"kiosk"\}"#.into()),
+            ])),
+    );
+    register_test("send_session/send_session-direct.trycmd")
+        .env(
+            "SENTRY_DSN",
+            format!(
+                "http://test@{}/4",
+                server_url().trim_start_matches("http://")
+            ),
+        )
+        .env("SENTRY_SPOOL_DIR", spool_dir.path().to_str().unwrap());
+}
@@ -0,0 +1,42 @@
+use mockito::{server_url, Matcher};
+use tempfile::tempdir;
+
+use crate::integration::{mock_endpoint, register_test, EndpointOptions};
+
+#[test]
+fn command_send_transaction_help() {
+    register_test("send_transaction/send_transaction-help.trycmd");
+}
+
+#[test]
+fn command_send_transaction() {
+    register_test("send_transaction/send_transaction.trycmd");
+}
+
+#[test]
+fn command_send_transaction_invalid() {
+    register_test("send_transaction/send_transaction-invalid.trycmd");
+}
+
+#[test]
+fn command_send_transaction_direct() {
+    let spool_dir = tempdir().unwrap();
+    let _server = mock_endpoint(
+        EndpointOptions::new("POST", "/api/5/envelope/", 200)
+            .with_response_body("{}")
+            .with_matcher(Matcher::AllOf(vec![
+                Matcher::Regex(r#""transaction":"nightly-build""#.into()),
+                Matcher::Regex(r#""op":"link""#.into()),
+                Matcher::Regex(r#""release":"1.0.0""#.into()),
+            ])),
+    );
+    register_test("send_transaction/send_transaction-direct.trycmd")
+        .env(
+            "SENTRY_DSN",
+            format!(
+                "http://test@{}/5",
+                server_url().trim_start_matches("http://")
+            ),
+        )
+        .env("SENTRY_SPOOL_DIR", spool_dir.path().to_str().unwrap());
+}
@@ -0,0 +1,51 @@
+use crate::integration::register_test;
+
+#[test]
+fn command_sourcemaps_bundle_help() {
+    register_test("sourcemaps/sourcemaps-bundle-help.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_bundle() {
+    register_test("sourcemaps/sourcemaps-bundle.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_bundle_compose() {
+    register_test("sourcemaps/sourcemaps-bundle-compose.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_bundle_detect() {
+    register_test("sourcemaps/sourcemaps-bundle-detect.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_bundle_source_root() {
+    register_test("sourcemaps/sourcemaps-bundle-source-root.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_bundle_optimize() {
+    register_test("sourcemaps/sourcemaps-bundle-optimize.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_bundle_optimize_existing_source() {
+    register_test("sourcemaps/sourcemaps-bundle-optimize-existing.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_bundle_hermes() {
+    register_test("sourcemaps/sourcemaps-bundle-hermes.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_bundle_hermes_debug_id() {
+    register_test("sourcemaps/sourcemaps-bundle-hermes-debug-id.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_bundle_hermes_no_reference() {
+    register_test("sourcemaps/sourcemaps-bundle-hermes-no-reference.trycmd");
+}
@@ -0,0 +1,11 @@
+use crate::integration::register_test;
+
+#[test]
+fn command_sourcemaps_compose_help() {
+    register_test("sourcemaps/sourcemaps-compose-help.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_compose() {
+    register_test("sourcemaps/sourcemaps-compose.trycmd");
+}
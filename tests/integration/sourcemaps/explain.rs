@@ -385,3 +385,33 @@ fn command_sourcemaps_explain_print_sourcemap() {
 
     register_test("sourcemaps/sourcemaps-explain-print-sourcemap.trycmd");
 }
+
+#[test]
+fn command_sourcemaps_explain_local() {
+    register_test("sourcemaps/sourcemaps-explain-local.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_explain_local_bundle() {
+    register_test("sourcemaps/sourcemaps-explain-local-bundle.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_explain_local_dist_mismatch() {
+    register_test("sourcemaps/sourcemaps-explain-local-dist-mismatch.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_explain_local_url_prefix() {
+    register_test("sourcemaps/sourcemaps-explain-local-url-prefix.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_explain_local_missing_sources() {
+    register_test("sourcemaps/sourcemaps-explain-local-missing-sources.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_explain_local_requires_event_file() {
+    register_test("sourcemaps/sourcemaps-explain-local-requires-event-file.trycmd");
+}
@@ -0,0 +1,11 @@
+use crate::integration::register_test;
+
+#[test]
+fn command_sourcemaps_inject_help() {
+    register_test("sourcemaps/sourcemaps-inject-help.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_inject() {
+    register_test("sourcemaps/sourcemaps-inject.trycmd");
+}
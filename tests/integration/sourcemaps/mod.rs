@@ -1,8 +1,14 @@
 use crate::integration::register_test;
 
+mod bundle;
+mod compose;
 mod explain;
+mod inject;
 mod resolve;
+mod symbolicate;
 mod upload;
+mod upload_bundle;
+mod validate;
 
 #[test]
 fn command_sourcemaps_help() {
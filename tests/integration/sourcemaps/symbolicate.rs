@@ -0,0 +1,21 @@
+use crate::integration::register_test;
+
+#[test]
+fn command_sourcemaps_symbolicate_help() {
+    register_test("sourcemaps/sourcemaps-symbolicate-help.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_symbolicate() {
+    register_test("sourcemaps/sourcemaps-symbolicate.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_symbolicate_bundle() {
+    register_test("sourcemaps/sourcemaps-symbolicate-bundle.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_symbolicate_event() {
+    register_test("sourcemaps/sourcemaps-symbolicate-event.trycmd");
+}
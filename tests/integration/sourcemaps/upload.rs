@@ -50,8 +50,52 @@ fn command_sourcemaps_upload_skip_already_uploaded() {
     register_test("sourcemaps/sourcemaps-upload-skip-already-uploaded.trycmd");
 }
 
+#[test]
+fn command_sourcemaps_upload_debug_id() {
+    let _upload_endpoints = mock_common_upload_endpoints();
+    let _files = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            200,
+        )
+        .with_response_body("[]"),
+    );
+
+    register_test("sourcemaps/sourcemaps-upload-debug-id.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_upload_optimize_existing() {
+    let _upload_endpoints = mock_common_upload_endpoints();
+    let _files = mock_endpoint(
+        EndpointOptions::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            200,
+        )
+        .with_response_body(
+            r#"[{
+                "id": "1337",
+                "name": "~/node_modules/lib/index.js",
+                "headers": {},
+                "size": 47,
+                "sha1": "38ed853073df85147960ea3a5bced6170ec389b0",
+                "dateCreated": "2022-05-12T11:08:01.496220Z"
+            }]"#,
+        ),
+    );
+
+    register_test("sourcemaps/sourcemaps-upload-optimize-existing.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_upload_bundle_optimize() {
+    register_test("sourcemaps/sourcemaps-upload-bundle-optimize.trycmd");
+}
+
 // Endpoints need to be bound, as they need to live long enough for test to finish
-fn mock_common_upload_endpoints() -> Vec<Mock> {
+pub fn mock_common_upload_endpoints() -> Vec<Mock> {
     let chunk_upload_response = format!(
         "{{
             \"url\": \"{}/api/0/organizations/wat-org/chunk-upload/\",
@@ -0,0 +1,24 @@
+use crate::integration::register_test;
+
+use super::upload::mock_common_upload_endpoints;
+
+#[test]
+fn command_sourcemaps_upload_bundle_help() {
+    register_test("sourcemaps/sourcemaps-upload-bundle-help.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_upload_bundle() {
+    let _upload_endpoints = mock_common_upload_endpoints();
+    register_test("sourcemaps/sourcemaps-upload-bundle.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_upload_bundle_release_mismatch() {
+    register_test("sourcemaps/sourcemaps-upload-bundle-release-mismatch.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_upload_bundle_project_mismatch() {
+    register_test("sourcemaps/sourcemaps-upload-bundle-project-mismatch.trycmd");
+}
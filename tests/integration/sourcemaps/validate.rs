@@ -0,0 +1,31 @@
+use crate::integration::register_test;
+
+#[test]
+fn command_sourcemaps_validate_help() {
+    register_test("sourcemaps/sourcemaps-validate-help.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_validate() {
+    register_test("sourcemaps/sourcemaps-validate.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_validate_errors() {
+    register_test("sourcemaps/sourcemaps-validate-errors.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_validate_json() {
+    register_test("sourcemaps/sourcemaps-validate-json.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_validate_junit() {
+    register_test("sourcemaps/sourcemaps-validate-junit.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_validate_fail_on_warning() {
+    register_test("sourcemaps/sourcemaps-validate-fail-on-warning.trycmd");
+}